@@ -1,7 +1,343 @@
 use crate::prelude::*;
 
+#[macro_use]
+pub mod options;
+
+pub mod config;
+pub mod monkey_react;
+pub mod monkey_reply;
+pub mod optin;
+pub mod optout;
+pub mod optouts;
 pub mod react;
+pub mod response;
 pub mod speak;
+pub mod trigger;
+
+pub use options::*;
+
+pub const COMMANDS: &[&dyn Command] = &[
+    &config::ConfigCommand,
+    &monkey_react::MonkeyReactCommand,
+    &monkey_reply::MonkeyReplyCommand,
+    &optin::OptInCommand,
+    &optout::OptOutCommand,
+    &optouts::OptOutsCommand,
+    &react::ReactCommand,
+    &response::ResponseCommand,
+    &speak::SpeakCommand,
+    &trigger::TriggerCommand,
+];
+
+#[async_trait]
+pub trait Command: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn create(&self) -> CreateCommand;
+
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()>;
+
+    async fn run_autocomplete(&self, _: &Context, _: &CommandInteraction) -> Result<()> {
+        Err(anyhow!(
+            "autocomplete is not supported by \"{}\"",
+            self.name()
+        ))
+    }
+    async fn run_component(
+        &self,
+        _: &Context,
+        _: &ComponentInteraction,
+        _: CustomId,
+    ) -> Result<()> {
+        Err(anyhow!(
+            "components are not supported by \"{}\"",
+            self.name()
+        ))
+    }
+    async fn run_modal(&self, _: &Context, _: &ModalInteraction, _: CustomId) -> Result<()> {
+        Err(anyhow!("modals are not supported by \"{}\"", self.name()))
+    }
+}
+
+pub fn get_target(command: &CommandInteraction) -> Result<&Message> {
+    match command.data.target() {
+        Some(ResolvedTarget::Message(message)) => Ok(message),
+        _ => Err(anyhow!("No valid message!")),
+    }
+}
+
+// accepts a bare message id within the current channel or a full message link within the guild
+pub async fn resolve_message(
+    ctx: &Context,
+    command: &CommandInteraction,
+    text: &str,
+) -> Result<Message> {
+    let text = text.trim();
+    let invalid = || anyhow!("\"{text}\" is not a message ID or link!");
+
+    let (channel, message) = if text.bytes().all(|b| b.is_ascii_digit()) {
+        (
+            command.channel_id,
+            text.parse::<u64>().map_err(|_| invalid())?,
+        )
+    } else {
+        let path = text
+            .split_once("/channels/")
+            .map(|(_, path)| path)
+            .ok_or_else(invalid)?;
+        let mut ids = path.trim_end_matches('/').split('/');
+        let (Some(guild), Some(channel), Some(message), None) =
+            (ids.next(), ids.next(), ids.next(), ids.next())
+        else {
+            return Err(invalid());
+        };
+
+        if command.guild_id.map(|id| id.to_string()).as_deref() != Some(guild) {
+            return Err(anyhow!("That message is not within this guild!"));
+        }
+
+        let channel = channel.parse::<u64>().map_err(|_| invalid())?;
+
+        if channel == 0 {
+            return Err(invalid());
+        }
+
+        (
+            ChannelId::new(channel),
+            message.parse().map_err(|_| invalid())?,
+        )
+    };
+
+    if message == 0 {
+        return Err(invalid());
+    }
+
+    // the link's guild is only its text, so the channel itself must be checked too
+    if channel != command.channel_id {
+        let inaccessible = || anyhow!("That message is missing or inaccessible!");
+        let Channel::Guild(found) = channel.to_channel(ctx).await.map_err(|_| inaccessible())?
+        else {
+            return Err(inaccessible());
+        };
+
+        if Some(found.guild_id) != command.guild_id {
+            return Err(anyhow!("That message is not within this guild!"));
+        }
+    }
+
+    channel
+        .message(ctx, MessageId::new(message))
+        .await
+        .map_err(|_| anyhow!("That message is missing or inaccessible!"))
+}
+
+pub async fn last_message(ctx: &Context, channel: ChannelId) -> Result<Message> {
+    channel
+        .messages(ctx, GetMessages::new().limit(1))
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("No valid message!"))
+}
+
+pub fn get_input<'m>(modal: &'m ModalInteraction, id: &str) -> Result<&'m str> {
+    modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| &row.components)
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input) if input.custom_id == id => {
+                Some(input.value.as_str())
+            }
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("missing data for \"{id}\""))
+}
+
+pub const SUGGESTION_LIMIT: usize = 25;
+pub const SUGGESTION_LENGTH: usize = 100;
+
+pub fn is_suggested(candidate: &str, partial: &str) -> bool {
+    candidate
+        .to_lowercase()
+        .contains(&partial.trim().to_lowercase())
+}
+
+pub fn get_partial(command: &CommandInteraction) -> Result<&str> {
+    command
+        .data
+        .autocomplete()
+        .map(|option| option.value)
+        .ok_or_else(|| anyhow!("missing focused option"))
+}
+
+pub async fn suggest(
+    ctx: &Context,
+    command: &CommandInteraction,
+    choices: impl IntoIterator<Item = (String, String)>,
+) -> Result<()> {
+    // discord rejects the whole response if any choice is too long
+    let fits = |text: &str| text.chars().count() <= SUGGESTION_LENGTH;
+    let response = choices
+        .into_iter()
+        .filter(|(name, value)| fits(name) && fits(value))
+        .take(SUGGESTION_LIMIT)
+        .fold(
+            CreateAutocompleteResponse::new(),
+            |response, (name, value)| response.add_string_choice(name, value),
+        );
+
+    command
+        .create_response(ctx, CreateInteractionResponse::Autocomplete(response))
+        .await
+        .map_err(Into::into)
+}
+
+pub fn find_command(name: &str) -> Result<&'static dyn Command> {
+    COMMANDS
+        .iter()
+        .find(|command| command.name() == name)
+        .copied()
+        .ok_or_else(|| anyhow!("unknown command \"{name}\""))
+}
+
+// components and modals are routed by the command name leading their custom id
+pub fn find_handler(custom_id: &str) -> Result<(&'static dyn Command, CustomId)> {
+    let id = CustomId::decode(custom_id)?;
+
+    Ok((find_command(&id.name)?, id))
+}
+
+// the state of a page button is the list's own state followed by the page it leads to
+pub fn page_buttons(
+    name: &str,
+    state: &str,
+    page: usize,
+    pages: usize,
+) -> Result<Vec<CreateActionRow>> {
+    if pages <= 1 {
+        return Ok(vec![]);
+    }
+
+    let button = |label: &str, target: usize, disabled: bool| -> Result<CreateButton> {
+        let id = CustomId::new(name, format!("{state}:{target}")).encode()?;
+
+        Ok(CreateButton::new(id)
+            .label(label)
+            .style(ButtonStyle::Secondary)
+            .disabled(disabled))
+    };
+
+    Ok(vec![CreateActionRow::Buttons(vec![
+        button("Previous", page.saturating_sub(1).max(1), page <= 1)?,
+        button("Next", (page + 1).min(pages), page >= pages)?,
+    ])])
+}
+
+pub fn get_page_state(id: &CustomId) -> Result<(&str, usize)> {
+    let (state, page) = id
+        .state
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow!("This interaction is invalid!"))?;
+
+    Ok((state, page.parse()?))
+}
+
+// only the confirm button carries the action's state, cancelling needs none
+pub fn confirm_buttons(name: &str, state: &str) -> Result<Vec<CreateActionRow>> {
+    let confirm = CustomId::new(name, format!("confirm:{state}")).encode()?;
+    let cancel = CustomId::new(name, "cancel").encode()?;
+
+    Ok(vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(confirm)
+            .label("Confirm")
+            .style(ButtonStyle::Danger),
+        CreateButton::new(cancel)
+            .label("Cancel")
+            .style(ButtonStyle::Secondary),
+    ])])
+}
+
+pub fn paginate(
+    name: &str,
+    state: &str,
+    title: String,
+    empty: &str,
+    lines: &[String],
+    page: usize,
+    page_size: usize,
+) -> Result<(CreateEmbed, Vec<CreateActionRow>)> {
+    let pages = ((lines.len() + page_size - 1) / page_size).max(1);
+    let page = page.clamp(1, pages);
+    let shown = lines
+        .iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let description = if shown.is_empty() {
+        format!("> *{empty}*")
+    } else {
+        shown.join("\n")
+    };
+
+    let embed = CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .title(title)
+        .description(description)
+        .footer(CreateEmbedFooter::new(format!("Page {page}/{pages}")));
+
+    Ok((embed, page_buttons(name, state, page, pages)?))
+}
+
+pub async fn reply_embed(
+    ctx: &Context,
+    command: &CommandInteraction,
+    embed: CreateEmbed,
+) -> Result<()> {
+    reply_page(ctx, command, (embed, vec![])).await
+}
+
+pub async fn reply_page(
+    ctx: &Context,
+    command: &CommandInteraction,
+    (embed, components): (CreateEmbed, Vec<CreateActionRow>),
+) -> Result<()> {
+    let follow_up = CreateInteractionResponseFollowup::new()
+        .embed(embed)
+        .components(components);
+
+    command.create_followup(ctx, follow_up).await?;
+    Ok(())
+}
+
+pub async fn update_page(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    (embed, components): (CreateEmbed, Vec<CreateActionRow>),
+) -> Result<()> {
+    let message = CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .components(components);
+
+    component
+        .create_response(ctx, CreateInteractionResponse::UpdateMessage(message))
+        .await?;
+    Ok(())
+}
+
+pub async fn edit_page(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    (embed, components): (CreateEmbed, Vec<CreateActionRow>),
+) -> Result<()> {
+    let edit = EditInteractionResponse::new()
+        .embed(embed)
+        .components(components);
+
+    component.edit_response(ctx, edit).await?;
+    Ok(())
+}
 
 macro_rules! get_fn {
     ($id:ident($inner:path) -> $ret:ty) => {
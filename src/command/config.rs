@@ -0,0 +1,179 @@
+use crate::prelude::*;
+
+pub const NAME: &str = "config";
+pub const GROUP_CHANNELS: &str = "channels";
+pub const SUBCOMMAND_ALLOW: &str = "allow";
+pub const SUBCOMMAND_DENY: &str = "deny";
+pub const SUBCOMMAND_RESET: &str = "reset";
+pub const SUBCOMMAND_LIST: &str = "list";
+pub const OPTION_FEATURE: &str = "feature";
+pub const OPTION_CHANNEL: &str = "channel";
+pub const OPTION_ROLE: &str = "role";
+
+options! {
+    struct TargetOptions {
+        #[option(OPTION_FEATURE, "Which automatic response to configure", react_or_reply)]
+        feature: String,
+        #[option(OPTION_CHANNEL, "A channel or category")]
+        channel: Option<ChannelId>,
+        #[option(OPTION_ROLE, "A role")]
+        role: Option<RoleId>,
+    }
+}
+
+options! {
+    struct ListOptions {
+        #[option(OPTION_FEATURE, "Which automatic response to configure", react_or_reply)]
+        feature: String,
+    }
+}
+
+fn check_feature(feature: &str) -> Result<&str> {
+    match feature {
+        "react" | "reply" => Ok(feature),
+        _ => Err(anyhow!("Unknown feature \"{feature}\"")),
+    }
+}
+
+fn read_access(guild: GuildId, dir: &str) -> Result<Stored<'_, Access>> {
+    Stored::read_or_else(Some(guild), dir, Access::KEY, Kind::Ron, Access::default)
+}
+
+pub struct ConfigCommand;
+
+#[async_trait]
+impl Command for ConfigCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .description("Configure where the monkey may go bananas")
+            .dm_permission(false)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::SubCommandGroup,
+                    GROUP_CHANNELS,
+                    "Configure which channels and roles get automatic responses",
+                )
+                .add_sub_option(subcommand(
+                    SUBCOMMAND_ALLOW,
+                    "Only respond within the given channels or to the given roles",
+                    TargetOptions::create(),
+                ))
+                .add_sub_option(subcommand(
+                    SUBCOMMAND_DENY,
+                    "Never respond within the given channels or to the given roles",
+                    TargetOptions::create(),
+                ))
+                .add_sub_option(subcommand(
+                    SUBCOMMAND_RESET,
+                    "Remove the given channels or roles from both lists",
+                    TargetOptions::create(),
+                ))
+                .add_sub_option(subcommand(
+                    SUBCOMMAND_LIST,
+                    "List the allowed and denied channels and roles",
+                    ListOptions::create(),
+                )),
+            )
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        command.defer_ephemeral(ctx).await?;
+
+        let guild = command
+            .guild_id
+            .ok_or_else(|| anyhow!("Configuration is only available within a guild!"))?;
+        let o = &command.data.options();
+        let o = get_subcommand_group(o, GROUP_CHANNELS)?;
+
+        let embed = if let Ok(o) = get_subcommand(o, SUBCOMMAND_ALLOW) {
+            run_targets(guild, o, SUBCOMMAND_ALLOW)?
+        } else if let Ok(o) = get_subcommand(o, SUBCOMMAND_DENY) {
+            run_targets(guild, o, SUBCOMMAND_DENY)?
+        } else if let Ok(o) = get_subcommand(o, SUBCOMMAND_RESET) {
+            run_targets(guild, o, SUBCOMMAND_RESET)?
+        } else if let Ok(o) = get_subcommand(o, SUBCOMMAND_LIST) {
+            run_list(guild, o)?
+        } else {
+            return Err(anyhow!("Unknown subcommand!"));
+        };
+
+        reply_embed(ctx, command, embed).await
+    }
+}
+
+fn run_targets(guild: GuildId, o: &[ResolvedOption<'_>], name: &str) -> Result<CreateEmbed> {
+    let TargetOptions {
+        feature,
+        channel,
+        role,
+    } = TargetOptions::parse(o)?;
+    let dir = check_feature(&feature)?;
+
+    if channel.is_none() && role.is_none() {
+        return Err(anyhow!("Provide a channel, a role, or both!"));
+    }
+
+    let mut access = read_access(guild, dir)?;
+    let Access { allow, deny } = &mut *access;
+
+    for targets in [&mut *allow, &mut *deny] {
+        targets.channels.retain(|id| Some(*id) != channel);
+        targets.roles.retain(|id| Some(*id) != role);
+    }
+
+    let (verb, targets) = match name {
+        SUBCOMMAND_ALLOW => ("Allowed", Some(allow)),
+        SUBCOMMAND_DENY => ("Denied", Some(deny)),
+        _ => ("Reset", None),
+    };
+
+    if let Some(targets) = targets {
+        targets.channels.extend(channel);
+        targets.roles.extend(role);
+    }
+
+    access.storage_write()?;
+
+    let mentions = channel
+        .map(|id| format!("<#{id}>"))
+        .into_iter()
+        .chain(role.map(|id| format!("<@&{id}>")))
+        .collect::<Vec<_>>();
+
+    Ok(CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .description(format!(
+            "{verb} {} for automatic {dir} responses",
+            mentions.join(" and ")
+        )))
+}
+
+fn describe(targets: &Targets) -> String {
+    let mentions = targets
+        .channels
+        .iter()
+        .map(|id| format!("<#{id}>"))
+        .chain(targets.roles.iter().map(|id| format!("<@&{id}>")))
+        .collect::<Vec<_>>();
+
+    if mentions.is_empty() {
+        "> *None*".to_string()
+    } else {
+        mentions.join(", ")
+    }
+}
+
+fn run_list(guild: GuildId, o: &[ResolvedOption<'_>]) -> Result<CreateEmbed> {
+    let feature = ListOptions::parse(o)?.feature;
+    let dir = check_feature(&feature)?;
+    let access = read_access(guild, dir)?;
+
+    Ok(CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .title(format!("Automatic {dir} responses"))
+        .field("Allowed", describe(&access.allow), false)
+        .field("Denied", describe(&access.deny), false))
+}
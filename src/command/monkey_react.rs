@@ -0,0 +1,54 @@
+use crate::prelude::*;
+
+pub const NAME: &str = "Monkey react";
+pub const INPUT_EMOJIS: &str = "emojis";
+
+pub struct MonkeyReactCommand;
+
+#[async_trait]
+impl Command for MonkeyReactCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .kind(CommandType::Message)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .dm_permission(false)
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        let target = get_target(command)?;
+        let id = CustomId::new(NAME, target.id.to_string()).encode()?;
+        let input = CreateInputText::new(InputTextStyle::Short, "Emojis", INPUT_EMOJIS)
+            .placeholder("Up to 20 different emojis, space separated")
+            .required(true);
+        let modal = CreateModal::new(id, "Monkey see, monkey do!")
+            .components(vec![CreateActionRow::InputText(input)]);
+
+        command
+            .create_response(ctx, CreateInteractionResponse::Modal(modal))
+            .await?;
+        Ok(())
+    }
+    async fn run_modal(&self, ctx: &Context, modal: &ModalInteraction, id: CustomId) -> Result<()> {
+        modal.defer_ephemeral(ctx).await?;
+
+        let target = MessageId::new(id.state.parse()?);
+        let text = get_input(modal, INPUT_EMOJIS)?;
+        let (emojis, rejected) = react::parse_emojis(ctx, modal.guild_id, text).await?;
+
+        for emoji in emojis {
+            modal.channel_id.create_reaction(ctx, target, emoji).await?;
+        }
+
+        let mut content = "Live monkey reaction!!! ^".to_string();
+
+        if !rejected.is_empty() {
+            content = format!("{content}\n> {}", react::describe_rejected(&rejected));
+        }
+
+        let follow_up = CreateInteractionResponseFollowup::new().content(content);
+        modal.create_followup(ctx, follow_up).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::prelude::*;
+
+pub const NAME: &str = "Monkey reply";
+pub const INPUT_CONTENT: &str = "content";
+
+pub struct MonkeyReplyCommand;
+
+#[async_trait]
+impl Command for MonkeyReplyCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .kind(CommandType::Message)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .dm_permission(false)
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        let target = get_target(command)?;
+        let id = CustomId::new(NAME, target.id.to_string()).encode()?;
+        let input = CreateInputText::new(InputTextStyle::Paragraph, "Content", INPUT_CONTENT)
+            .placeholder("What should the monkey say?")
+            .max_length(2000)
+            .required(true);
+        let modal = CreateModal::new(id, "Speak, monkey!")
+            .components(vec![CreateActionRow::InputText(input)]);
+
+        command
+            .create_response(ctx, CreateInteractionResponse::Modal(modal))
+            .await?;
+        Ok(())
+    }
+    async fn run_modal(&self, ctx: &Context, modal: &ModalInteraction, id: CustomId) -> Result<()> {
+        modal.defer_ephemeral(ctx).await?;
+
+        let target = MessageId::new(id.state.parse()?);
+        let message = CreateMessage::new()
+            .content(get_input(modal, INPUT_CONTENT)?)
+            .reference_message((modal.channel_id, target));
+
+        modal.channel_id.send_message(ctx, message).await?;
+
+        let follow_up = CreateInteractionResponseFollowup::new().content("The monkey has spoken!");
+        modal.create_followup(ctx, follow_up).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::prelude::*;
+
+pub const NAME: &str = "optin";
+
+pub struct OptInCommand;
+
+#[async_trait]
+impl Command for OptInCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .description("Let the monkey react and reply to you again")
+            .dm_permission(true)
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        command.defer_ephemeral(ctx).await?;
+
+        let mut users = optout::read(command.guild_id)?;
+
+        if !users.remove(&command.user.id) {
+            let global = optout::read(None)?;
+
+            return Err(
+                if command.guild_id.is_some() && global.contains(&command.user.id) {
+                    anyhow!("You opted out everywhere, so opt back in from the monkey's DMs!")
+                } else {
+                    anyhow!("You have not opted out!")
+                },
+            );
+        }
+
+        users.storage_write()?;
+
+        let follow_up =
+            CreateInteractionResponseFollowup::new().content("Welcome back to the jungle!");
+        command.create_followup(ctx, follow_up).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptionError {
+    Missing(&'static str),
+    Invalid(&'static str, &'static str),
+}
+
+impl Display for OptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(name) => write!(f, "Missing the \"{name}\" option!"),
+            Self::Invalid(name, expected) => {
+                write!(f, "The \"{name}\" option must be {expected}!")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OptionError {}
+
+pub trait OptionField: Sized {
+    const KIND: CommandOptionType;
+    const REQUIRED: bool;
+
+    fn parse(name: &'static str, o: &[ResolvedOption<'_>]) -> Result<Self, OptionError>;
+}
+
+macro_rules! option_field {
+    ($type:ty, $kind:ident, $expected:literal, $pattern:pat => $value:expr) => {
+        impl OptionField for $type {
+            const KIND: CommandOptionType = CommandOptionType::$kind;
+            const REQUIRED: bool = true;
+
+            fn parse(name: &'static str, o: &[ResolvedOption<'_>]) -> Result<Self, OptionError> {
+                <Option<Self>>::parse(name, o)?.ok_or(OptionError::Missing(name))
+            }
+        }
+
+        impl OptionField for Option<$type> {
+            const KIND: CommandOptionType = CommandOptionType::$kind;
+            const REQUIRED: bool = false;
+
+            fn parse(name: &'static str, o: &[ResolvedOption<'_>]) -> Result<Self, OptionError> {
+                match o.iter().find(|r| r.name == name).map(|r| &r.value) {
+                    None => Ok(None),
+                    Some($pattern) => Ok(Some($value)),
+                    Some(_) => Err(OptionError::Invalid(name, $expected)),
+                }
+            }
+        }
+    };
+}
+
+option_field!(String, String, "text", ResolvedValue::String(v) => (*v).to_string());
+option_field!(bool, Boolean, "true or false", ResolvedValue::Boolean(v) => *v);
+option_field!(i64, Integer, "a whole number", ResolvedValue::Integer(v) => *v);
+option_field!(f64, Number, "a number", ResolvedValue::Number(v) => *v);
+option_field!(ChannelId, Channel, "a channel", ResolvedValue::Channel(v) => v.id);
+option_field!(RoleId, Role, "a role", ResolvedValue::Role(v) => v.id);
+option_field!(UserId, User, "a user", ResolvedValue::User(v, _) => v.id);
+
+// declares an options struct along with its command option list and parser, so they can't drift
+macro_rules! options {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                #[option($option:expr, $description:expr $(, $modify:expr)?)]
+                $field:ident: $type:ty,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $(pub $field: $type,)*
+        }
+
+        impl $name {
+            pub fn create() -> Vec<CreateCommandOption> {
+                vec![$({
+                    let option = CreateCommandOption::new(
+                        <$type as OptionField>::KIND,
+                        $option,
+                        $description,
+                    )
+                    .required(<$type as OptionField>::REQUIRED);
+                    $(
+                        let modify: fn(CreateCommandOption) -> CreateCommandOption = $modify;
+                        let option = modify(option);
+                    )?
+
+                    option
+                }),*]
+            }
+
+            pub fn parse(o: &[ResolvedOption<'_>]) -> Result<Self, OptionError> {
+                Ok(Self {
+                    $($field: <$type as OptionField>::parse($option, o)?,)*
+                })
+            }
+        }
+    };
+}
+
+pub fn react_or_reply(option: CreateCommandOption) -> CreateCommandOption {
+    option
+        .add_string_choice("React", "react")
+        .add_string_choice("Reply", "reply")
+}
+
+pub fn subcommand(
+    name: &str,
+    description: &str,
+    options: Vec<CreateCommandOption>,
+) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::SubCommand, name, description)
+        .set_sub_options(options)
+}
@@ -0,0 +1,46 @@
+use crate::prelude::*;
+
+pub const NAME: &str = "optout";
+
+pub const DIR: &str = "optout";
+pub const KEY: &str = "users";
+
+pub fn read(guild: Option<GuildId>) -> Result<Stored<'static, BTreeSet<UserId>>> {
+    Stored::read_or_else(guild, DIR, KEY, Kind::Ron, BTreeSet::new)
+}
+
+pub struct OptOutCommand;
+
+#[async_trait]
+impl Command for OptOutCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .description("Stop the monkey from reacting and replying to you")
+            .dm_permission(true)
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        command.defer_ephemeral(ctx).await?;
+
+        let mut users = read(command.guild_id)?;
+
+        if !users.insert(command.user.id) {
+            return Err(anyhow!("You have already opted out!"));
+        }
+
+        users.storage_write()?;
+
+        // opting out from DMs is stored globally and respected in every guild
+        let content = if command.guild_id.is_some() {
+            "The monkey will leave you alone in this server!"
+        } else {
+            "The monkey will leave you alone everywhere!"
+        };
+
+        let follow_up = CreateInteractionResponseFollowup::new().content(content);
+        command.create_followup(ctx, follow_up).await?;
+        Ok(())
+    }
+}
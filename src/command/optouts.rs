@@ -0,0 +1,107 @@
+use futures::future::try_join_all;
+
+use crate::prelude::*;
+
+pub const NAME: &str = "optouts";
+pub const OPTION_PAGE: &str = "page";
+
+pub const PAGE_SIZE: usize = 25;
+
+options! {
+    struct OptOutsOptions {
+        #[option(OPTION_PAGE, "The page to display", |o| o.min_int_value(1))]
+        page: Option<i64>,
+    }
+}
+
+// anything but the member being missing is a real error, rather than them having left
+async fn is_member(ctx: &Context, guild: GuildId, user: UserId) -> Result<bool> {
+    match guild.member(ctx, user).await {
+        Ok(_) => Ok(true),
+        Err(serenity::Error::Http(error))
+            if error
+                .status_code()
+                .is_some_and(|status| status.as_u16() == 404) =>
+        {
+            Ok(false)
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+// global opt-outs are kept across guilds, so only those of this guild's members are listed
+async fn list_page(
+    ctx: &Context,
+    guild: GuildId,
+    page: usize,
+) -> Result<(CreateEmbed, Vec<CreateActionRow>)> {
+    let global = optout::read(None)?;
+    let local = optout::read(Some(guild))?;
+    let members = try_join_all(global.iter().map(|id| is_member(ctx, guild, *id))).await?;
+    let mut lines = global
+        .iter()
+        .zip(members)
+        .filter(|(_, is_member)| *is_member)
+        .map(|(id, _)| format!("<@{id}> (global)"))
+        .collect::<Vec<_>>();
+
+    lines.extend(
+        local
+            .iter()
+            .filter(|id| !global.contains(id))
+            .map(|id| format!("<@{id}>")),
+    );
+
+    paginate(
+        NAME,
+        "users",
+        format!("{} opted out members", lines.len()),
+        "Nobody has opted out",
+        &lines,
+        page,
+        PAGE_SIZE,
+    )
+}
+
+pub struct OptOutsCommand;
+
+#[async_trait]
+impl Command for OptOutsCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .description("List the members who opted out of the monkey")
+            .dm_permission(false)
+            .set_options(OptOutsOptions::create())
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        command.defer_ephemeral(ctx).await?;
+
+        let guild = command
+            .guild_id
+            .ok_or_else(|| anyhow!("Opt-outs can only be listed within a guild!"))?;
+        let page = OptOutsOptions::parse(&command.data.options())?.page;
+        let page = usize::try_from(page.unwrap_or(1))?;
+
+        reply_page(ctx, command, list_page(ctx, guild, page).await?).await
+    }
+    async fn run_component(
+        &self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        id: CustomId,
+    ) -> Result<()> {
+        let guild = component
+            .guild_id
+            .ok_or_else(|| anyhow!("Opt-outs can only be listed within a guild!"))?;
+        let (_, page) = get_page_state(&id)?;
+
+        // members are fetched before the page can be built, which may outlast the deadline
+        component.defer(ctx).await?;
+
+        edit_page(ctx, component, list_page(ctx, guild, page).await?).await
+    }
+}
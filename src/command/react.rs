@@ -2,49 +2,156 @@ use crate::prelude::*;
 
 pub const NAME: &str = "react";
 pub const OPTION_EMOJIS: &str = "emojis";
+pub const OPTION_MESSAGE: &str = "message";
 
-pub fn new() -> CreateCommand {
-    CreateCommand::new(NAME)
-        .default_member_permissions(Permissions::ADMINISTRATOR)
-        .description("Monkey see, monkey do!")
-        .dm_permission(false)
-        .add_option(
-            CreateCommandOption::new(
-                CommandOptionType::String,
-                OPTION_EMOJIS,
-                "Up to 20 different emojis, space separated",
-            )
-            .required(true),
-        )
+options! {
+    struct ReactOptions {
+        #[option(
+            OPTION_EMOJIS,
+            "Up to 20 different emojis, space separated",
+            |o| o.set_autocomplete(true)
+        )]
+        emojis: String,
+        #[option(OPTION_MESSAGE, "A message ID or link to react to instead of the latest one")]
+        message: Option<String>,
+    }
 }
 
-pub async fn run_command(http: &Http, command: &CommandInteraction) -> Result<()> {
-    command.defer_ephemeral(http).await?;
+pub const MAX_REACTIONS: usize = 20;
 
-    let mut emojis = get_str(&command.data.options(), OPTION_EMOJIS)?
-        .split(' ')
-        .map_while(|slice| ReactionType::try_from(slice).ok())
-        .take(20)
+pub fn describe_rejected(rejected: &[String]) -> String {
+    let tokens = rejected
+        .iter()
+        .map(|token| format!("`{token}`"))
         .collect::<Vec<_>>();
 
-    emojis.dedup();
+    format!("Skipped {}", tokens.join(", "))
+}
+
+// guild emojis are only usable if they belong to the guild, so they're checked against its list
+pub async fn parse_emojis(
+    ctx: &Context,
+    guild: Option<GuildId>,
+    text: &str,
+) -> Result<(Vec<ReactionType>, Vec<String>)> {
+    let Tokenized {
+        emojis,
+        mut rejected,
+    } = tokenize(text);
+    let available = match guild {
+        Some(guild)
+            if emojis
+                .iter()
+                .any(|e| e.id().is_some() || e.is_guild_emoji()) =>
+        {
+            guild_emojis(ctx, guild).await?
+        }
+        _ => vec![],
+    };
+    let mut reactions = vec![];
+
+    for emoji in emojis {
+        let found = match emoji.id() {
+            Some(id) => Reaction::Id(id).resolve_in(&available),
+            None => emoji.resolve_in(&available),
+        };
 
-    if emojis.is_empty() {
-        return Err(anyhow!("No valid reactions provided!"));
+        // names are only known once resolved, so duplicates can still appear here
+        match found {
+            Some(found) if reactions.contains(&found) => {}
+            Some(found) => reactions.push(found),
+            None => rejected.push(emoji.to_string()),
+        }
     }
 
-    let message = command
-        .channel_id
-        .messages(http, GetMessages::new().limit(1))
-        .await?
-        .pop()
-        .ok_or_else(|| anyhow!("No valid message!"))?;
+    let mut reactions = reactions
+        .into_iter()
+        .map(ReactionType::from)
+        .collect::<Vec<_>>();
 
-    for emoji in emojis {
-        message.react(http, emoji).await?;
+    if reactions.len() > MAX_REACTIONS {
+        rejected.extend(reactions.drain(MAX_REACTIONS..).map(|r| r.to_string()));
+    }
+    if reactions.is_empty() {
+        return Err(if rejected.is_empty() {
+            anyhow!("No valid reactions provided!")
+        } else {
+            anyhow!(
+                "No valid reactions provided! {}",
+                describe_rejected(&rejected)
+            )
+        });
+    }
+
+    Ok((reactions, rejected))
+}
+
+pub struct ReactCommand;
+
+#[async_trait]
+impl Command for ReactCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .description("Monkey see, monkey do!")
+            .dm_permission(false)
+            .set_options(ReactOptions::create())
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        command.defer_ephemeral(ctx).await?;
+
+        let options = ReactOptions::parse(&command.data.options())?;
+        let (emojis, rejected) = parse_emojis(ctx, command.guild_id, &options.emojis).await?;
+        let message = match &options.message {
+            Some(text) => resolve_message(ctx, command, text).await?,
+            None => last_message(ctx, command.channel_id).await?,
+        };
+
+        for emoji in emojis {
+            message.react(ctx, emoji).await?;
+        }
+
+        let mut content = "Live monkey reaction!!! ^".to_string();
+
+        if !rejected.is_empty() {
+            content = format!("{content}\n> {}", describe_rejected(&rejected));
+        }
+
+        let follow_up = CreateInteractionResponseFollowup::new().content(content);
+        command.create_followup(ctx, follow_up).await?;
+        Ok(())
     }
+    async fn run_autocomplete(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        let partial = get_partial(command)?;
+        // earlier emojis are kept so each suggestion appends to what was already typed
+        let (typed, current) = partial.rsplit_once(' ').unwrap_or(("", partial));
+        let reactions = Cache::of(ctx).await?.get_layered::<WeightVec<Reaction>>(
+            command.guild_id,
+            "react",
+            "responses",
+            Kind::Ron,
+        )?;
+        let available = match command.guild_id {
+            Some(guild) if reactions.iter().any(|(r, _)| r.is_guild_emoji()) => {
+                guild_emojis(ctx, guild).await?
+            }
+            _ => vec![],
+        };
+        let choices = reactions
+            .iter()
+            .filter_map(|(reaction, _)| reaction.resolve_in(&available))
+            .map(|emoji| emoji.to_string())
+            .filter(|emoji| !typed.contains(emoji.as_str()) && is_suggested(emoji, current))
+            .map(|emoji| {
+                let value = format!("{typed} {emoji}").trim().to_string();
+
+                (value.clone(), value)
+            })
+            .collect::<Vec<_>>();
 
-    let follow_up = CreateInteractionResponseFollowup::new().content("Live monkey reaction!!! ^");
-    command.create_followup(http, follow_up).await?;
-    Ok(())
+        suggest(ctx, command, choices).await
+    }
 }
@@ -0,0 +1,364 @@
+use crate::prelude::*;
+
+pub const NAME: &str = "response";
+pub const SUBCOMMAND_ADD: &str = "add";
+pub const SUBCOMMAND_WEIGHT: &str = "weight";
+pub const SUBCOMMAND_REMOVE: &str = "remove";
+pub const SUBCOMMAND_LIST: &str = "list";
+pub const OPTION_KIND: &str = "kind";
+pub const OPTION_RESPONSE: &str = "response";
+pub const OPTION_EMOJI: &str = "emoji";
+pub const OPTION_WEIGHT: &str = "weight";
+pub const OPTION_INDEX: &str = "index";
+pub const OPTION_PAGE: &str = "page";
+
+pub const KEY: &str = "responses";
+pub const PAGE_SIZE: usize = 15;
+
+options! {
+    struct AddOptions {
+        #[option(OPTION_KIND, "Which response list to use", react_or_reply)]
+        kind: String,
+        #[option(
+            OPTION_RESPONSE,
+            "The reply text, or a single emoji, :shortcode: or guild emoji for reactions",
+            |o| o.max_length(1000).clone()
+        )]
+        response: String,
+        #[option(OPTION_WEIGHT, "The relative weight of the response")]
+        weight: f64,
+        #[option(OPTION_EMOJI, "An emoji to surround the reply text with")]
+        emoji: Option<String>,
+    }
+}
+
+options! {
+    struct WeightOptions {
+        #[option(OPTION_KIND, "Which response list to use", react_or_reply)]
+        kind: String,
+        #[option(
+            OPTION_INDEX,
+            "The position of the response in the list",
+            |o| o.min_int_value(1)
+        )]
+        index: i64,
+        #[option(OPTION_WEIGHT, "The relative weight of the response")]
+        weight: f64,
+    }
+}
+
+options! {
+    struct RemoveOptions {
+        #[option(OPTION_KIND, "Which response list to use", react_or_reply)]
+        kind: String,
+        #[option(
+            OPTION_INDEX,
+            "The position of the response in the list",
+            |o| o.min_int_value(1)
+        )]
+        index: i64,
+    }
+}
+
+options! {
+    struct ListOptions {
+        #[option(OPTION_KIND, "Which response list to use", react_or_reply)]
+        kind: String,
+        #[option(OPTION_PAGE, "The page to display", |o| o.min_int_value(1))]
+        page: Option<i64>,
+    }
+}
+
+trait Response: Clone + PartialEq + Serialize + for<'de> Deserialize<'de> {
+    fn parse(options: &AddOptions) -> Result<Self>;
+    fn display(&self) -> String;
+}
+
+fn parse_char(text: &str, name: &str) -> Result<char> {
+    let mut chars = text.trim().chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(anyhow!("\"{name}\" must be a single character emoji!")),
+    }
+}
+
+impl Response for Reaction {
+    fn parse(options: &AddOptions) -> Result<Self> {
+        options.response.parse()
+    }
+    fn display(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Response for (Template, Option<char>) {
+    fn parse(options: &AddOptions) -> Result<Self> {
+        let text = options.response.trim();
+        let emoji = match &options.emoji {
+            Some(emoji) => Some(parse_char(emoji, OPTION_EMOJI)?),
+            None => None,
+        };
+
+        if text.is_empty() {
+            return Err(anyhow!("Responses cannot be empty!"));
+        }
+
+        Ok((Template::new(text)?, emoji))
+    }
+    fn display(&self) -> String {
+        let (text, emoji) = self;
+        let emoji = emoji.map(|c| c.to_string()).unwrap_or_default();
+
+        format!("{emoji} {text} {emoji}").trim().to_string()
+    }
+}
+
+fn check_weight(weight: f64) -> Result<f64> {
+    if weight.is_finite() && weight > 0.0 {
+        Ok(weight)
+    } else {
+        Err(anyhow!("Weights must be positive, finite numbers!"))
+    }
+}
+
+fn check_index(index: i64, len: usize) -> Result<usize> {
+    let index = usize::try_from(index)?;
+
+    if (1..=len).contains(&index) {
+        Ok(index - 1)
+    } else {
+        Err(anyhow!("There is no response #{index}!"))
+    }
+}
+
+fn read_global<T: Response>(dir: &str) -> Result<Stored<'_, WeightVec<T>>> {
+    Stored::read(dir, KEY, Kind::Ron)
+}
+
+fn read_layer<T: Response>(guild: GuildId, dir: &str) -> Result<Stored<'_, Layer<WeightVec<T>>>> {
+    Stored::read_or_else(Some(guild), dir, KEY, Kind::Ron, || {
+        Layer::Extend(WeightVec::new())
+    })
+}
+
+pub struct ResponseCommand;
+
+#[async_trait]
+impl Command for ResponseCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .description("Manage what the monkey has to say")
+            .dm_permission(false)
+            .add_option(subcommand(
+                SUBCOMMAND_ADD,
+                "Add a response",
+                AddOptions::create(),
+            ))
+            .add_option(subcommand(
+                SUBCOMMAND_WEIGHT,
+                "Change the weight of a response",
+                WeightOptions::create(),
+            ))
+            .add_option(subcommand(
+                SUBCOMMAND_REMOVE,
+                "Remove a response",
+                RemoveOptions::create(),
+            ))
+            .add_option(subcommand(
+                SUBCOMMAND_LIST,
+                "List all responses and their chances",
+                ListOptions::create(),
+            ))
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        command.defer_ephemeral(ctx).await?;
+
+        let guild = command
+            .guild_id
+            .ok_or_else(|| anyhow!("Responses can only be managed within a guild!"))?;
+        let o = &command.data.options();
+        let (name, o) = [
+            SUBCOMMAND_ADD,
+            SUBCOMMAND_WEIGHT,
+            SUBCOMMAND_REMOVE,
+            SUBCOMMAND_LIST,
+        ]
+        .into_iter()
+        .find_map(|name| get_subcommand(o, name).ok().map(|o| (name, o)))
+        .ok_or_else(|| anyhow!("Unknown subcommand!"))?;
+
+        let page = match get_str(o, OPTION_KIND)? {
+            "react" => run_subcommand::<Reaction>(name, guild, "react", o)?,
+            "reply" => run_subcommand::<(Template, Option<char>)>(name, guild, "reply", o)?,
+            kind => return Err(anyhow!("Unknown response list \"{kind}\"")),
+        };
+
+        reply_page(ctx, command, page).await
+    }
+    async fn run_component(
+        &self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        id: CustomId,
+    ) -> Result<()> {
+        let guild = component
+            .guild_id
+            .ok_or_else(|| anyhow!("Responses can only be managed within a guild!"))?;
+        let page = match get_page_state(&id)? {
+            ("react", page) => list_page::<Reaction>(guild, "react", page)?,
+            ("reply", page) => list_page::<(Template, Option<char>)>(guild, "reply", page)?,
+            (kind, _) => return Err(anyhow!("Unknown response list \"{kind}\"")),
+        };
+
+        update_page(ctx, component, page).await
+    }
+}
+
+fn run_subcommand<T: Response>(
+    name: &str,
+    guild: GuildId,
+    dir: &str,
+    o: &[ResolvedOption<'_>],
+) -> Result<(CreateEmbed, Vec<CreateActionRow>)> {
+    let embed = match name {
+        SUBCOMMAND_ADD => run_add::<T>(guild, dir, o)?,
+        SUBCOMMAND_WEIGHT => run_weight::<T>(guild, dir, o)?,
+        SUBCOMMAND_REMOVE => run_remove::<T>(guild, dir, o)?,
+        SUBCOMMAND_LIST => {
+            let page = ListOptions::parse(o)?.page.unwrap_or(1);
+
+            return list_page::<T>(guild, dir, usize::try_from(page)?);
+        }
+        _ => return Err(anyhow!("Unknown subcommand!")),
+    };
+
+    Ok((embed, vec![]))
+}
+
+fn run_add<T: Response>(
+    guild: GuildId,
+    dir: &str,
+    o: &[ResolvedOption<'_>],
+) -> Result<CreateEmbed> {
+    let options = AddOptions::parse(o)?;
+    let response = T::parse(&options)?;
+    let weight = check_weight(options.weight)?;
+    let global = read_global::<T>(dir)?;
+    let mut layer = read_layer::<T>(guild, dir)?;
+
+    if layer.resolve(&global).iter().any(|(r, _)| r == &response) {
+        return Err(anyhow!("That is already a response!"));
+    }
+
+    match &mut *layer {
+        Layer::Extend(list) | Layer::Override(list) => list.push(response.clone(), weight),
+    }
+
+    layer.storage_write()?;
+
+    Ok(CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .description(format!(
+            "Added \"{}\" to the {dir} responses",
+            response.display()
+        )))
+}
+
+fn run_weight<T: Response>(
+    guild: GuildId,
+    dir: &str,
+    o: &[ResolvedOption<'_>],
+) -> Result<CreateEmbed> {
+    let options = WeightOptions::parse(o)?;
+    let weight = check_weight(options.weight)?;
+    let global = read_global::<T>(dir)?;
+    let mut layer = read_layer::<T>(guild, dir)?;
+    let mut effective = layer.resolve(&global);
+    let index = check_index(options.index, effective.len())?;
+    let response = effective[index].0.display();
+
+    // entries past the global list in an extension live in the guild's own list
+    match &mut *layer {
+        Layer::Extend(list) if index >= global.len() => list[index - global.len()].1 = weight,
+        _ => {
+            effective[index].1 = weight;
+            *layer = Layer::Override(effective);
+        }
+    }
+
+    layer.storage_write()?;
+
+    Ok(CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .description(format!("Changed the weight of \"{response}\" to {weight}")))
+}
+
+fn run_remove<T: Response>(
+    guild: GuildId,
+    dir: &str,
+    o: &[ResolvedOption<'_>],
+) -> Result<CreateEmbed> {
+    let options = RemoveOptions::parse(o)?;
+    let global = read_global::<T>(dir)?;
+    let mut layer = read_layer::<T>(guild, dir)?;
+    let mut effective = layer.resolve(&global);
+    let index = check_index(options.index, effective.len())?;
+
+    let removed = match &mut *layer {
+        Layer::Extend(list) if index >= global.len() => list.remove(index - global.len()),
+        _ => {
+            let removed = effective.remove(index);
+            *layer = Layer::Override(effective);
+            removed
+        }
+    };
+
+    let (response, _) = removed.ok_or_else(|| anyhow!("There is no response #{}!", index + 1))?;
+
+    layer.storage_write()?;
+
+    Ok(CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .description(format!(
+            "Removed \"{}\" from the {dir} responses",
+            response.display()
+        )))
+}
+
+fn list_page<T: Response>(
+    guild: GuildId,
+    dir: &str,
+    page: usize,
+) -> Result<(CreateEmbed, Vec<CreateActionRow>)> {
+    let global = read_global::<T>(dir)?;
+    let responses = read_layer::<T>(guild, dir)?.resolve(&global);
+    let total = responses.total();
+    let lines = responses
+        .iter()
+        .enumerate()
+        .map(|(index, (response, weight))| {
+            let chance = weight / total * 100.0;
+            let index = index + 1;
+
+            format!(
+                "`#{index}` {} - {chance:.2}% ({weight})",
+                response.display()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    paginate(
+        NAME,
+        dir,
+        format!("{} {dir} responses", responses.len()),
+        "No responses",
+        &lines,
+        page,
+        PAGE_SIZE,
+    )
+}
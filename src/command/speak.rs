@@ -3,51 +3,59 @@ use crate::prelude::*;
 pub const NAME: &str = "speak";
 pub const OPTION_CONTENT: &str = "content";
 pub const OPTION_REPLY: &str = "reply";
-
-pub fn new() -> CreateCommand {
-    CreateCommand::new(NAME)
-        .default_member_permissions(Permissions::ADMINISTRATOR)
-        .description("Speak, monkey!")
-        .dm_permission(false)
-        .add_option(
-            CreateCommandOption::new(
-                CommandOptionType::String,
-                OPTION_CONTENT,
-                "What should the monkey say?",
-            )
-            .max_length(2000)
-            .clone()
-            .required(true),
-        )
-        .add_option(CreateCommandOption::new(
-            CommandOptionType::Boolean,
-            OPTION_REPLY,
-            "Whether to reply to the last sent message",
-        ))
+pub const OPTION_MESSAGE: &str = "message";
+
+options! {
+    struct SpeakOptions {
+        #[option(OPTION_CONTENT, "What should the monkey say?", |o| o.max_length(2000).clone())]
+        content: String,
+        #[option(OPTION_REPLY, "Whether to reply to the last sent message")]
+        reply: Option<bool>,
+        #[option(OPTION_MESSAGE, "A message ID or link to reply to instead of the latest one")]
+        message: Option<String>,
+    }
 }
 
-pub async fn run_command(http: &Http, command: &CommandInteraction) -> Result<()> {
-    command.defer_ephemeral(http).await?;
-
-    let o = &command.data.options();
-    let content = get_str(o, OPTION_CONTENT)?;
-    let reply = get_bool(o, OPTION_REPLY).unwrap_or_default();
-    let mut message = CreateMessage::new().content(content);
-
-    if reply {
-        let reference = command
-            .channel_id
-            .messages(http, GetMessages::new().limit(1))
-            .await?
-            .pop()
-            .ok_or_else(|| anyhow!("No valid message!"))?;
+pub struct SpeakCommand;
 
-        message = message.reference_message(&reference);
+#[async_trait]
+impl Command for SpeakCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .description("Speak, monkey!")
+            .dm_permission(false)
+            .set_options(SpeakOptions::create())
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        command.defer_ephemeral(ctx).await?;
+
+        let options = SpeakOptions::parse(&command.data.options())?;
+        let reference = match &options.message {
+            Some(text) => Some(resolve_message(ctx, command, text).await?),
+            None if options.reply.unwrap_or_default() => {
+                Some(last_message(ctx, command.channel_id).await?)
+            }
+            None => None,
+        };
+        let mut message = CreateMessage::new().content(options.content);
+
+        // a linked message may live in another channel, so the reply is sent alongside it
+        let channel = reference
+            .as_ref()
+            .map_or(command.channel_id, |r| r.channel_id);
+
+        if let Some(reference) = &reference {
+            message = message.reference_message(reference);
+        }
+
+        channel.send_message(ctx, message).await?;
+
+        let follow_up = CreateInteractionResponseFollowup::new().content("The monkey has spoken!");
+        command.create_followup(ctx, follow_up).await?;
+        Ok(())
     }
-
-    command.channel_id.send_message(http, message).await?;
-
-    let follow_up = CreateInteractionResponseFollowup::new().content("The monkey has spoken!");
-    command.create_followup(http, follow_up).await?;
-    Ok(())
 }
@@ -0,0 +1,399 @@
+use crate::prelude::*;
+
+pub const NAME: &str = "trigger";
+pub const SUBCOMMAND_ADD: &str = "add";
+pub const SUBCOMMAND_REMOVE: &str = "remove";
+pub const SUBCOMMAND_LIST: &str = "list";
+pub const SUBCOMMAND_TEST: &str = "test";
+pub const OPTION_KIND: &str = "kind";
+pub const OPTION_TRIGGER: &str = "trigger";
+pub const OPTION_CHANCE: &str = "chance";
+pub const OPTION_MODE: &str = "mode";
+pub const OPTION_FUZZY: &str = "fuzzy";
+pub const OPTION_PAGE: &str = "page";
+pub const OPTION_CONTENT: &str = "content";
+
+pub const KEY: &str = "triggers";
+pub const PAGE_SIZE: usize = 20;
+
+fn mode_choices(option: CreateCommandOption) -> CreateCommandOption {
+    Mode::ALL.iter().fold(option, |option, mode| {
+        option.add_string_choice(mode.name(), mode.name())
+    })
+}
+
+options! {
+    struct AddOptions {
+        #[option(OPTION_KIND, "Which trigger list to use", react_or_reply)]
+        kind: String,
+        #[option(OPTION_TRIGGER, "The trigger to add", |o| o.max_length(100).clone())]
+        trigger: String,
+        #[option(
+            OPTION_CHANCE,
+            "The percent chance that the trigger fires",
+            |o| o.min_number_value(0.0).max_number_value(100.0)
+        )]
+        chance: Option<f64>,
+        #[option(OPTION_MODE, "How the trigger is matched", mode_choices)]
+        mode: Option<String>,
+        #[option(OPTION_FUZZY, "Whether the trigger also matches slight misspellings")]
+        fuzzy: Option<bool>,
+    }
+}
+
+options! {
+    struct RemoveOptions {
+        #[option(OPTION_KIND, "Which trigger list to use", react_or_reply)]
+        kind: String,
+        #[option(OPTION_TRIGGER, "The trigger to remove", |o| o.set_autocomplete(true))]
+        trigger: String,
+    }
+}
+
+options! {
+    struct ListOptions {
+        #[option(OPTION_KIND, "Which trigger list to use", react_or_reply)]
+        kind: String,
+        #[option(OPTION_PAGE, "The page to display", |o| o.min_int_value(1))]
+        page: Option<i64>,
+    }
+}
+
+options! {
+    struct TestOptions {
+        #[option(OPTION_KIND, "Which trigger list to use", react_or_reply)]
+        kind: String,
+        #[option(OPTION_CONTENT, "The text to test", |o| o.max_length(2000).clone())]
+        content: String,
+    }
+}
+
+fn check_kind(kind: &str) -> Result<&str> {
+    match kind {
+        "react" | "reply" => Ok(kind),
+        _ => Err(anyhow!("Unknown trigger list \"{kind}\"")),
+    }
+}
+
+fn read_layer(guild: GuildId, dir: &str) -> Result<Stored<'_, Layer<TriggerSet>>> {
+    Stored::read_or_else(Some(guild), dir, KEY, Kind::Ron, || {
+        Layer::Extend(TriggerSet::default())
+    })
+}
+
+fn read_effective(guild: GuildId, dir: &str) -> Result<TriggerSet> {
+    let global = Stored::<TriggerSet>::read(dir, KEY, Kind::Ron)?;
+
+    Ok(read_layer(guild, dir)?.resolve(&global))
+}
+
+fn get_mode(mode: Option<&str>) -> Result<Mode> {
+    match mode {
+        None => Ok(Mode::default()),
+        Some(mode) => Mode::ALL
+            .into_iter()
+            .find(|m| m.name() == mode)
+            .ok_or_else(|| anyhow!("Unknown trigger mode \"{mode}\"")),
+    }
+}
+
+fn describe(trigger: &Trigger) -> String {
+    let mut details = vec![];
+
+    if trigger.mode != Mode::default() {
+        details.push(trigger.mode.name().to_string());
+    }
+    if trigger.fuzzy {
+        details.push("fuzzy".to_string());
+    }
+    if trigger.chance < 1.0 {
+        // chances are stored as fractions, so the percentage is rounded to hide float noise
+        let percent = format!("{:.2}", trigger.chance * 100.0);
+        let percent = percent.trim_end_matches('0').trim_end_matches('.');
+
+        details.push(format!("{percent}%"));
+    }
+
+    if details.is_empty() {
+        format!("`{trigger}`")
+    } else {
+        format!("`{trigger}` ({})", details.join(", "))
+    }
+}
+
+pub struct TriggerCommand;
+
+#[async_trait]
+impl Command for TriggerCommand {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+    fn create(&self) -> CreateCommand {
+        CreateCommand::new(NAME)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .description("Manage what makes the monkey go bananas")
+            .dm_permission(false)
+            .add_option(subcommand(
+                SUBCOMMAND_ADD,
+                "Add a trigger",
+                AddOptions::create(),
+            ))
+            .add_option(subcommand(
+                SUBCOMMAND_REMOVE,
+                "Remove a trigger",
+                RemoveOptions::create(),
+            ))
+            .add_option(subcommand(
+                SUBCOMMAND_LIST,
+                "List all triggers",
+                ListOptions::create(),
+            ))
+            .add_option(subcommand(
+                SUBCOMMAND_TEST,
+                "Check which triggers some text would match",
+                TestOptions::create(),
+            ))
+    }
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        command.defer_ephemeral(ctx).await?;
+
+        let guild = command
+            .guild_id
+            .ok_or_else(|| anyhow!("Triggers can only be managed within a guild!"))?;
+        let o = &command.data.options();
+
+        if let Ok(o) = get_subcommand(o, SUBCOMMAND_ADD) {
+            run_add(ctx, command, guild, o).await
+        } else if let Ok(o) = get_subcommand(o, SUBCOMMAND_REMOVE) {
+            run_remove(ctx, command, guild, o).await
+        } else if let Ok(o) = get_subcommand(o, SUBCOMMAND_LIST) {
+            run_list(ctx, command, guild, o).await
+        } else if let Ok(o) = get_subcommand(o, SUBCOMMAND_TEST) {
+            run_test(ctx, command, guild, o).await
+        } else {
+            Err(anyhow!("Unknown subcommand!"))
+        }
+    }
+    async fn run_component(
+        &self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        id: CustomId,
+    ) -> Result<()> {
+        let guild = component
+            .guild_id
+            .ok_or_else(|| anyhow!("Triggers can only be managed within a guild!"))?;
+
+        if id.state == "cancel" {
+            let embed = CreateEmbed::new()
+                .color(Color::BLURPLE)
+                .description("Cancelled");
+
+            return update_page(ctx, component, (embed, vec![])).await;
+        }
+        if let Some(("confirm", state)) = id.state.split_once(':') {
+            let (dir, digest) = state
+                .split_once(':')
+                .ok_or_else(|| anyhow!("This interaction is invalid!"))?;
+            let embed = remove(guild, check_kind(dir)?, digest)?;
+
+            return update_page(ctx, component, (embed, vec![])).await;
+        }
+
+        let (dir, page) = get_page_state(&id)?;
+
+        update_page(ctx, component, list_page(guild, check_kind(dir)?, page)?).await
+    }
+    async fn run_autocomplete(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        let guild = command
+            .guild_id
+            .ok_or_else(|| anyhow!("Triggers can only be managed within a guild!"))?;
+        let partial = get_partial(command)?;
+        let o = &command.data.options();
+        let o = get_subcommand(o, SUBCOMMAND_REMOVE)?;
+
+        // the list can't be known until a kind has been picked
+        let Ok(kind) = get_str(o, OPTION_KIND) else {
+            return suggest(ctx, command, []).await;
+        };
+
+        let mut triggers = read_effective(guild, check_kind(kind)?)?.to_vec();
+
+        triggers.sort_by(|a, b| a.text.cmp(&b.text));
+
+        let choices = triggers
+            .into_iter()
+            .filter(|trigger| is_suggested(&trigger.text, partial))
+            .map(|trigger| (trigger.text.clone(), trigger.text));
+
+        suggest(ctx, command, choices).await
+    }
+}
+
+async fn run_add(
+    ctx: &Context,
+    command: &CommandInteraction,
+    guild: GuildId,
+    o: &[ResolvedOption<'_>],
+) -> Result<()> {
+    let options = AddOptions::parse(o)?;
+    let dir = check_kind(&options.kind)?;
+    let chance = options.chance.map_or(1.0, |c| c / 100.0);
+    let mode = get_mode(options.mode.as_deref())?;
+    let fuzzy = options.fuzzy.unwrap_or(false);
+    let text = options.trigger.trim();
+    // regular expressions are case sensitive in their syntax, so they're kept as-is
+    let text = if mode == Mode::Regex {
+        text.to_string()
+    } else {
+        text.to_lowercase()
+    };
+
+    if text.is_empty() {
+        return Err(anyhow!("Triggers cannot be empty!"));
+    }
+    if read_effective(guild, dir)?.iter().any(|t| t.text == text) {
+        return Err(anyhow!("\"{text}\" is already a trigger!"));
+    }
+
+    let trigger = Trigger::new(text, chance, mode, fuzzy).map_err(|error| anyhow!("{error}!"))?;
+    let mut layer = read_layer(guild, dir)?;
+
+    match &mut *layer {
+        Layer::Extend(list) | Layer::Override(list) => list.push(trigger.clone()),
+    }
+
+    layer.storage_write()?;
+
+    let embed = CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .description(format!(
+            "Added {} to the {dir} triggers",
+            describe(&trigger)
+        ));
+
+    reply_embed(ctx, command, embed).await
+}
+
+// the trigger is referenced by its digest, as its text may not fit within a custom id
+async fn run_remove(
+    ctx: &Context,
+    command: &CommandInteraction,
+    guild: GuildId,
+    o: &[ResolvedOption<'_>],
+) -> Result<()> {
+    let options = RemoveOptions::parse(o)?;
+    let dir = check_kind(&options.kind)?;
+    let text = options.trigger.trim();
+    let lower = text.to_lowercase();
+    let trigger = read_effective(guild, dir)?
+        .iter()
+        .find(|t| t.text == text || t.text == lower)
+        .cloned()
+        .ok_or_else(|| anyhow!("\"{text}\" is not a trigger!"))?;
+
+    let state = format!("{dir}:{}", CustomId::digest(&trigger.text));
+    let embed = CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .description(format!(
+            "Remove {} from the {dir} triggers?",
+            describe(&trigger)
+        ));
+
+    reply_page(ctx, command, (embed, confirm_buttons(NAME, &state)?)).await
+}
+
+fn remove(guild: GuildId, dir: &str, digest: &str) -> Result<CreateEmbed> {
+    let is_match = |t: &Trigger| CustomId::digest(&t.text) == digest;
+    let global = Stored::<TriggerSet>::read(dir, KEY, Kind::Ron)?;
+    let mut layer = read_layer(guild, dir)?;
+    let mut effective = layer.resolve(&global);
+    let trigger = effective
+        .iter()
+        .find(|t| is_match(t))
+        .cloned()
+        .ok_or_else(|| anyhow!("That trigger has already been removed!"))?;
+
+    effective.retain(|t| !is_match(t));
+
+    // global triggers can only be dropped by overriding the global list entirely
+    *layer = match &*layer {
+        Layer::Extend(list) if !global.iter().any(is_match) => {
+            let mut list = list.clone();
+            list.retain(|t| !is_match(t));
+            Layer::Extend(list)
+        }
+        _ => Layer::Override(effective),
+    };
+
+    layer.storage_write()?;
+
+    Ok(CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .description(format!("Removed \"{trigger}\" from the {dir} triggers")))
+}
+
+fn list_page(
+    guild: GuildId,
+    dir: &str,
+    page: usize,
+) -> Result<(CreateEmbed, Vec<CreateActionRow>)> {
+    let mut triggers = read_effective(guild, dir)?.to_vec();
+
+    triggers.sort_by(|a, b| a.text.cmp(&b.text));
+
+    let lines = triggers.iter().map(describe).collect::<Vec<_>>();
+
+    paginate(
+        NAME,
+        dir,
+        format!("{} {dir} triggers", triggers.len()),
+        "No triggers",
+        &lines,
+        page,
+        PAGE_SIZE,
+    )
+}
+
+async fn run_list(
+    ctx: &Context,
+    command: &CommandInteraction,
+    guild: GuildId,
+    o: &[ResolvedOption<'_>],
+) -> Result<()> {
+    let options = ListOptions::parse(o)?;
+    let dir = check_kind(&options.kind)?;
+    let page = usize::try_from(options.page.unwrap_or(1))?;
+
+    reply_page(ctx, command, list_page(guild, dir, page)?).await
+}
+
+async fn run_test(
+    ctx: &Context,
+    command: &CommandInteraction,
+    guild: GuildId,
+    o: &[ResolvedOption<'_>],
+) -> Result<()> {
+    let options = TestOptions::parse(o)?;
+    let dir = check_kind(&options.kind)?;
+    let triggers = read_effective(guild, dir)?;
+    let matches = options
+        .content
+        .search(&triggers)
+        .iter()
+        .map(|m| format!("{} matched {m}", describe(m.trigger)))
+        .collect::<Vec<_>>();
+
+    let description = if matches.is_empty() {
+        "> *No triggers matched*".to_string()
+    } else {
+        matches.join("\n")
+    };
+
+    let embed = CreateEmbed::new()
+        .color(Color::BLURPLE)
+        .title(format!("Found {} {dir} trigger matches", matches.len()))
+        .description(description);
+
+    reply_embed(ctx, command, embed).await
+}
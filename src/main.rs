@@ -3,7 +3,8 @@
 #![warn(clippy::todo, clippy::unimplemented)]
 #![allow(clippy::module_name_repetitions, clippy::unused_async)]
 #![allow(clippy::wildcard_imports)]
-#![feature(is_some_and, const_trait_impl)]
+#![feature(is_some_and, const_trait_impl, once_cell)]
+#![cfg_attr(test, feature(test))]
 
 use clap::Parser;
 use prelude::*;
@@ -32,8 +33,12 @@ async fn main() -> Result<()> {
     let logger = Logger::new(quiet, !no_store)?;
     logger.info("Starting...")?;
 
+    let events = Events::new(logger)?;
+    let cache = events.cache.clone();
+
     let mut client = Client::builder(token()?, INTENTS)
-        .event_handler(Events::new(logger))
+        .type_map_insert::<Cache>(cache)
+        .event_handler(events)
         .await?;
 
     client.start_autosharded().await.map_err(Into::into)
@@ -3,10 +3,11 @@ pub use chrono::prelude::*;
 pub use serde::{Deserialize, Serialize};
 pub use serenity::{
     all::{
-        async_trait, Client, Color, CommandInteraction, CommandOptionType, ComponentInteraction,
-        Context, GatewayIntents, Http, Interaction, Member, Message, ModalInteraction,
-        PartialChannel, PartialMember, Permissions, ReactionType, Ready, ResolvedOption,
-        ResolvedValue, Role, User,
+        async_trait, ActionRowComponent, Attachment, ButtonStyle, Channel, Client, Color,
+        CommandInteraction, CommandOptionType, CommandType, ComponentInteraction, Context, Embed,
+        Emoji, GatewayIntents, Http, InputTextStyle, Interaction, Member, Message,
+        ModalInteraction, PartialChannel, PartialMember, Permissions, ReactionType, Ready,
+        ResolvedOption, ResolvedTarget, ResolvedValue, Role, StickerItem, User,
     },
     builder::*,
     gateway::ActivityData,
@@ -19,9 +20,15 @@ pub use std::{
 };
 
 pub use crate::command::*;
+pub use crate::utility::access::*;
+pub use crate::utility::cache::*;
+pub use crate::utility::cooldown::*;
+pub use crate::utility::custom_id::*;
+pub use crate::utility::emoji::*;
 pub use crate::utility::events::*;
 pub use crate::utility::logger::*;
 pub use crate::utility::random::*;
 pub use crate::utility::search::*;
 pub use crate::utility::stored::*;
+pub use crate::utility::template::*;
 pub use crate::utility::*;
@@ -1,15 +1,22 @@
 use crate::prelude::*;
 
+pub mod access;
+pub mod cache;
+pub mod cooldown;
+pub mod custom_id;
+pub mod emoji;
 pub mod events;
 pub mod logger;
 pub mod random;
 pub mod search;
 pub mod stored;
+pub mod template;
 
 pub const DEV_BUILD: bool = cfg!(debug_assertions);
 pub const INTENTS: GatewayIntents = GatewayIntents::DIRECT_MESSAGES
     .union(GatewayIntents::DIRECT_MESSAGE_REACTIONS)
     .union(GatewayIntents::GUILDS)
+    .union(GatewayIntents::GUILD_EMOJIS_AND_STICKERS)
     .union(GatewayIntents::GUILD_MESSAGES)
     .union(GatewayIntents::GUILD_MESSAGE_REACTIONS)
     .union(GatewayIntents::MESSAGE_CONTENT);
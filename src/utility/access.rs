@@ -0,0 +1,44 @@
+use crate::prelude::*;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Targets {
+    pub channels: BTreeSet<ChannelId>,
+    pub roles: BTreeSet<RoleId>,
+}
+
+impl Targets {
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty() && self.roles.is_empty()
+    }
+
+    fn has_channel(&self, channels: &[ChannelId]) -> bool {
+        channels.iter().any(|id| self.channels.contains(id))
+    }
+    fn has_role(&self, roles: &[RoleId]) -> bool {
+        roles.iter().any(|id| self.roles.contains(id))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Access {
+    pub allow: Targets,
+    pub deny: Targets,
+}
+
+impl Access {
+    pub const KEY: &str = "access";
+
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    // channels include the parents of the message's channel, so categories apply to everything within
+    pub fn permits(&self, channels: &[ChannelId], roles: &[RoleId]) -> bool {
+        let channel = self.allow.channels.is_empty() || self.allow.has_channel(channels);
+        let role = self.allow.roles.is_empty() || self.allow.has_role(roles);
+
+        channel && role && !self.deny.has_channel(channels) && !self.deny.has_role(roles)
+    }
+}
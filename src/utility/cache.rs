@@ -0,0 +1,295 @@
+use std::{
+    any::Any,
+    fs::create_dir_all,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
+
+use notify::{recommended_watcher, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serenity::prelude::TypeMapKey;
+
+use crate::prelude::*;
+
+type Value = Arc<dyn Any + Send + Sync>;
+type Loader = fn(&Path, Kind) -> Result<Value>;
+type Entries = RwLock<BTreeMap<PathBuf, Entry>>;
+type Resolved = RwLock<BTreeMap<PathBuf, Value>>;
+
+struct Entry {
+    kind: Kind,
+    loader: Loader,
+    value: Value,
+}
+
+fn load<T>(path: &Path, kind: Kind) -> Result<Value>
+where
+    T: Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static,
+{
+    Ok(Arc::new(Stored::<T>::read_path(path, kind)?))
+}
+
+fn load_opt<T>(path: &Path, kind: Kind) -> Result<Value>
+where
+    T: Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static,
+{
+    match Stored::<T>::read_path(path, kind) {
+        Ok(value) => Ok(Arc::new(Some(value))),
+        Err(error) if is_not_found(&error) => Ok(Arc::new(None::<T>)),
+        Err(error) => Err(error),
+    }
+}
+
+#[derive(Clone)]
+pub struct Cache {
+    base: PathBuf,
+    entries: Arc<Entries>,
+    resolved: Arc<Resolved>,
+    generation: Arc<AtomicU64>,
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
+impl Cache {
+    pub fn new(logger: Logger) -> Result<Self> {
+        let base = std::env::current_dir()?;
+        let root = base.join(Stored::<()>::DIR);
+        let entries = Arc::new(Entries::default());
+        let resolved = Arc::new(Resolved::default());
+        let generation = Arc::new(AtomicU64::new(0));
+
+        create_dir_all(&root)?;
+
+        let handles = (
+            Arc::clone(&entries),
+            Arc::clone(&resolved),
+            Arc::clone(&generation),
+        );
+        let mut watcher = recommended_watcher(move |event| {
+            let (entries, resolved, generation) = &handles;
+
+            if Self::handle_event(entries, &logger, event) {
+                generation.fetch_add(1, Ordering::SeqCst);
+
+                if let Ok(mut resolved) = resolved.write() {
+                    resolved.clear();
+                }
+            }
+        })?;
+
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+
+        Ok(Self {
+            base,
+            entries,
+            resolved,
+            generation,
+            _watcher: Arc::new(Mutex::new(watcher)),
+        })
+    }
+
+    pub async fn of(ctx: &Context) -> Result<Self> {
+        ctx.data
+            .read()
+            .await
+            .get::<Self>()
+            .cloned()
+            .ok_or_else(|| anyhow!("missing data cache"))
+    }
+
+    fn handle_event(entries: &Entries, logger: &Logger, event: notify::Result<Event>) -> bool {
+        let event = match event {
+            Ok(event) => event,
+            Err(error) => {
+                logger.warn(format!("Error watching data: {error}")).ok();
+                return false;
+            }
+        };
+
+        if matches!(event.kind, EventKind::Access(_) | EventKind::Any) {
+            return false;
+        }
+
+        let mut changed = false;
+
+        for path in &event.paths {
+            let Ok(guard) = entries.read() else {
+                return changed;
+            };
+            // a new directory is watched only once created, so files already written within it
+            // never report an event of their own
+            let stale = guard
+                .iter()
+                .filter(|(cached, _)| cached.starts_with(path))
+                .map(|(cached, entry)| (cached.clone(), entry.kind, entry.loader))
+                .collect::<Vec<_>>();
+
+            drop(guard);
+
+            for (path, kind, loader) in stale {
+                let value = match loader(&path, kind) {
+                    Ok(value) => value,
+                    Err(error) => {
+                        let path = path.display();
+                        logger
+                            .warn(format!(
+                                "Error reloading \"{path}\", keeping last value: {error}"
+                            ))
+                            .ok();
+                        continue;
+                    }
+                };
+
+                let Ok(mut guard) = entries.write() else {
+                    return changed;
+                };
+
+                if let Some(entry) = guard.get_mut(&path) {
+                    entry.value = value;
+                    changed = true;
+                    logger.info(format!("Reloaded \"{}\"", path.display())).ok();
+                }
+            }
+        }
+
+        changed
+    }
+
+    fn get_entry(&self, path: PathBuf, kind: Kind, loader: Loader) -> Result<Value> {
+        let cached = self
+            .entries
+            .read()
+            .map_err(|_| anyhow!("cache lock poisoned"))?
+            .get(&path)
+            .map(|entry| Arc::clone(&entry.value));
+
+        if let Some(value) = cached {
+            return Ok(value);
+        }
+
+        let value = loader(&path, kind)?;
+        let entry = Entry {
+            kind,
+            loader,
+            value: Arc::clone(&value),
+        };
+
+        self.entries
+            .write()
+            .map_err(|_| anyhow!("cache lock poisoned"))?
+            .insert(path, entry);
+
+        Ok(value)
+    }
+
+    pub fn get<T>(&self, guild: Option<GuildId>, dir: &str, key: &str, kind: Kind) -> Result<Arc<T>>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static,
+    {
+        let path = self.base.join(Stored::<T>::path_of(guild, dir, key, kind));
+
+        self.get_entry(path, kind, load::<T>)?
+            .downcast()
+            .map_err(|_| anyhow!("invalid cached type for \"{dir}/{key}\""))
+    }
+    pub fn get_opt<T>(
+        &self,
+        guild: Option<GuildId>,
+        dir: &str,
+        key: &str,
+        kind: Kind,
+    ) -> Result<Arc<Option<T>>>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static,
+    {
+        let path = self.base.join(Stored::<T>::path_of(guild, dir, key, kind));
+
+        self.get_entry(path, kind, load_opt::<T>)?
+            .downcast()
+            .map_err(|_| anyhow!("invalid cached type for \"{dir}/{key}\""))
+    }
+    pub fn get_scoped<T>(
+        &self,
+        guild: Option<GuildId>,
+        dir: &str,
+        key: &str,
+        kind: Kind,
+    ) -> Result<T>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + 'static,
+    {
+        if let Some(guild) = guild {
+            if let Some(value) = &*self.get_opt::<T>(Some(guild), dir, key, kind)? {
+                return Ok(value.clone());
+            }
+        }
+
+        Ok((*self.get_opt::<T>(None, dir, key, kind)?)
+            .clone()
+            .unwrap_or_default())
+    }
+    pub fn get_layered<T>(
+        &self,
+        guild: Option<GuildId>,
+        dir: &str,
+        key: &str,
+        kind: Kind,
+    ) -> Result<Arc<T>>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Clone + Merge + Send + Sync + 'static,
+    {
+        let Some(guild) = guild else {
+            return self.get(None, dir, key, kind);
+        };
+
+        let path = self
+            .base
+            .join(Stored::<T>::path_of(Some(guild), dir, key, kind));
+        let cached = self
+            .resolved
+            .read()
+            .map_err(|_| anyhow!("cache lock poisoned"))?
+            .get(&path)
+            .map(Arc::clone);
+
+        if let Some(value) = cached {
+            return value
+                .downcast()
+                .map_err(|_| anyhow!("invalid cached type for \"{dir}/{key}\""));
+        }
+
+        let generation = self.generation.load(Ordering::SeqCst);
+        let global = self.get::<T>(None, dir, key, kind)?;
+        let layer = self.get_opt::<Layer<T>>(Some(guild), dir, key, kind)?;
+
+        let value = match &*layer {
+            Some(layer) => Arc::new(layer.resolve(&global)),
+            None => global,
+        };
+
+        let mut resolved = self
+            .resolved
+            .write()
+            .map_err(|_| anyhow!("cache lock poisoned"))?;
+
+        if generation == self.generation.load(Ordering::SeqCst) {
+            resolved.insert(path, Arc::clone(&value) as Value);
+        }
+
+        Ok(value)
+    }
+}
+
+// commands share the event handler's cache through the client's data
+impl TypeMapKey for Cache {
+    type Value = Self;
+}
+
+impl std::fmt::Debug for Cache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cache")
+            .field("base", &self.base)
+            .finish_non_exhaustive()
+    }
+}
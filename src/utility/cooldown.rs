@@ -0,0 +1,93 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Cooldown {
+    pub channel: u64,
+    pub user: u64,
+    pub trigger: u64,
+}
+
+impl Cooldown {
+    pub const KEY: &str = "cooldowns";
+
+    pub const fn duration(&self, scope: &Scope) -> Duration {
+        Duration::from_secs(match scope {
+            Scope::Channel(_) => self.channel,
+            Scope::User(_) => self.user,
+            Scope::Trigger(_) => self.trigger,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    Channel(ChannelId),
+    User(UserId),
+    Trigger(String),
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Channel(id) => write!(f, "channel <#{id}>"),
+            Self::User(id) => write!(f, "user <@{id}>"),
+            Self::Trigger(word) => write!(f, "trigger \"{word}\""),
+        }
+    }
+}
+
+type Key = (String, Option<GuildId>, Scope);
+
+#[derive(Debug, Default)]
+pub struct Cooldowns {
+    active: Mutex<BTreeMap<Key, Instant>>,
+}
+
+impl Cooldowns {
+    fn scopes(message: &Message, trigger: &str) -> [Scope; 3] {
+        [
+            Scope::Channel(message.channel_id),
+            Scope::User(message.author.id),
+            Scope::Trigger(trigger.to_string()),
+        ]
+    }
+
+    pub fn try_start(
+        &self,
+        dir: &str,
+        message: &Message,
+        trigger: &str,
+        config: &Cooldown,
+    ) -> Result<Option<Scope>> {
+        let now = Instant::now();
+        let mut active = self
+            .active
+            .lock()
+            .map_err(|_| anyhow!("cooldown lock poisoned"))?;
+
+        active.retain(|_, until| *until > now);
+
+        let scopes = Self::scopes(message, trigger);
+        let key = |scope: &Scope| (dir.to_string(), message.guild_id, scope.clone());
+
+        if let Some(scope) = scopes.iter().find(|s| active.contains_key(&key(s))) {
+            return Ok(Some(scope.clone()));
+        }
+
+        for scope in &scopes {
+            let duration = config.duration(scope);
+
+            if !duration.is_zero() {
+                active.insert(key(scope), now + duration);
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,188 @@
+use std::{fmt::Write, sync::OnceLock};
+
+use hmac::{Hmac, Mac};
+use rand::{thread_rng, Rng};
+use sha2::{Digest, Sha256};
+
+use crate::prelude::*;
+
+// regenerated on every start, so ids from a previous run are rejected as tampered
+static SECRET: OnceLock<[u8; 32]> = OnceLock::new();
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        write!(hex, "{b:02x}").ok();
+        hex
+    })
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomId {
+    pub name: String,
+    pub state: String,
+    pub created: i64,
+}
+
+impl CustomId {
+    pub const MAX_LENGTH: usize = 100;
+    pub const MAX_AGE: i64 = 15 * 60;
+    // truncated so that the state has room within the length limit
+    pub const SIGNATURE_LENGTH: usize = 12;
+
+    pub fn new(name: impl Into<String>, state: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            state: state.into(),
+            created: Utc::now().timestamp(),
+        }
+    }
+
+    fn mac(name: &str, created: i64, state: &str) -> Result<Hmac<Sha256>> {
+        let secret = SECRET.get_or_init(|| thread_rng().gen());
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret)
+            .map_err(|_| anyhow!("invalid custom id secret"))?;
+
+        mac.update(format!("{name}:{created}:{state}").as_bytes());
+        Ok(mac)
+    }
+
+    /// Returns a short, stable digest of `text` for states that can't fit it whole
+    pub fn digest(text: &str) -> String {
+        to_hex(&Sha256::digest(text.as_bytes())[..8])
+    }
+
+    pub fn encode(&self) -> Result<String> {
+        if self.name.contains(':') {
+            return Err(anyhow!("invalid custom id name \"{}\"", self.name));
+        }
+
+        let Self {
+            name,
+            state,
+            created,
+        } = self;
+        let signature = Self::mac(name, *created, state)?.finalize().into_bytes();
+        let signature = to_hex(&signature[..Self::SIGNATURE_LENGTH]);
+        let id = format!("{name}:{created}:{state}:{signature}");
+
+        if id.len() > Self::MAX_LENGTH {
+            return Err(anyhow!("custom id \"{id}\" is too long"));
+        }
+
+        Ok(id)
+    }
+
+    pub fn decode(id: &str) -> Result<Self> {
+        let invalid = || anyhow!("This interaction is invalid!");
+        let (name, rest) = id.split_once(':').ok_or_else(invalid)?;
+        let (created, rest) = rest.split_once(':').ok_or_else(invalid)?;
+        let (state, signature) = rest.rsplit_once(':').ok_or_else(invalid)?;
+        let created = created.parse::<i64>().map_err(|_| invalid())?;
+        let signature = from_hex(signature).ok_or_else(invalid)?;
+
+        if signature.len() != Self::SIGNATURE_LENGTH {
+            return Err(invalid());
+        }
+
+        Self::mac(name, created, state)?
+            .verify_truncated_left(&signature)
+            .map_err(|_| invalid())?;
+
+        if Utc::now().timestamp() - created > Self::MAX_AGE {
+            return Err(anyhow!("This interaction has expired!"));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            state: state.to_string(),
+            created,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_error(result: Result<CustomId>, message: &str) -> bool {
+        matches!(result, Err(error) if error.to_string() == message)
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let id = CustomId::new("trigger", "react:2");
+
+        assert_eq!(CustomId::decode(&id.encode()?)?, id);
+
+        // states may hold colons of their own
+        let id = CustomId::new("trigger", "confirm:react:0123abcd");
+
+        assert_eq!(CustomId::decode(&id.encode()?)?, id);
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_state() -> Result<()> {
+        let encoded = CustomId::new("trigger", "react:2").encode()?;
+        let tampered = encoded.replacen("react:2", "reply:2", 1);
+
+        assert!(is_error(
+            CustomId::decode(&tampered),
+            "This interaction is invalid!"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_signature() -> Result<()> {
+        let encoded = CustomId::new("trigger", "react:2").encode()?;
+        let (rest, signature) = encoded.rsplit_once(':').unwrap_or_default();
+        let flipped = if signature.starts_with('0') { "1" } else { "0" };
+        let tampered = format!("{rest}:{flipped}{}", &signature[1..]);
+        let truncated = format!("{rest}:{}", &signature[2..]);
+
+        assert!(is_error(
+            CustomId::decode(&tampered),
+            "This interaction is invalid!"
+        ));
+        assert!(is_error(
+            CustomId::decode(&truncated),
+            "This interaction is invalid!"
+        ));
+        assert!(is_error(
+            CustomId::decode(rest),
+            "This interaction is invalid!"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_name() {
+        assert!(CustomId::new("trig:ger", "react:2").encode().is_err());
+    }
+
+    #[test]
+    fn expired() -> Result<()> {
+        let id = CustomId {
+            created: Utc::now().timestamp() - CustomId::MAX_AGE - 1,
+            ..CustomId::new("trigger", "react:2")
+        };
+
+        assert!(is_error(
+            CustomId::decode(&id.encode()?),
+            "This interaction has expired!"
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,375 @@
+use std::str::FromStr;
+
+use serde::{
+    de::{self, Visitor},
+    Deserializer, Serializer,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EmojiToken {
+    Unicode(String),
+    Custom {
+        id: EmojiId,
+        name: String,
+        animated: bool,
+    },
+}
+
+impl EmojiToken {
+    pub const fn id(&self) -> Option<EmojiId> {
+        match self {
+            Self::Unicode(_) => None,
+            Self::Custom { id, .. } => Some(*id),
+        }
+    }
+}
+
+impl Display for EmojiToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unicode(emoji) => write!(f, "{emoji}"),
+            Self::Custom { id, name, animated } => {
+                write!(f, "<{}:{name}:{id}>", if *animated { "a" } else { "" })
+            }
+        }
+    }
+}
+
+impl From<EmojiToken> for ReactionType {
+    fn from(token: EmojiToken) -> Self {
+        match token {
+            EmojiToken::Unicode(emoji) => Self::Unicode(emoji),
+            EmojiToken::Custom { id, name, animated } => Self::Custom {
+                animated,
+                id,
+                name: Some(name),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tokenized {
+    pub emojis: Vec<Reaction>,
+    pub rejected: Vec<String>,
+}
+
+// the variation selector is optional in typed text, so lookups retry without it
+fn find_unicode(grapheme: &str) -> Option<&'static emojis::Emoji> {
+    emojis::get(grapheme).or_else(|| emojis::get(&grapheme.replace('\u{fe0f}', "")))
+}
+
+fn parse_custom(token: &str) -> Option<EmojiToken> {
+    let inner = token.strip_prefix('<')?.strip_suffix('>')?;
+    let (animated, inner) = match inner.strip_prefix("a:") {
+        Some(inner) => (true, inner),
+        None => (false, inner.strip_prefix(':')?),
+    };
+    let (name, id) = inner.split_once(':')?;
+    let id = id.parse::<u64>().ok().filter(|id| *id != 0)?;
+
+    (!name.is_empty()).then(|| EmojiToken::Custom {
+        id: EmojiId::new(id),
+        name: name.to_string(),
+        animated,
+    })
+}
+
+// names that aren't shortcodes may still belong to one of the guild's emojis
+fn next_token(text: &str) -> (Result<Reaction, String>, usize) {
+    let word = text.find(char::is_whitespace).unwrap_or(text.len());
+
+    if text.starts_with('<') {
+        let end = text[..word].find('>').map_or(word, |end| end + 1);
+        let token = &text[..end];
+        let emoji = parse_custom(token).map(Reaction::Emoji);
+
+        return (emoji.ok_or_else(|| token.to_string()), end);
+    }
+
+    if let Some(code) = text[..word].strip_prefix(':') {
+        let Some(end) = code.find(':') else {
+            return (Err(text[..word].to_string()), word);
+        };
+        let token = &text[..end + 2];
+        let name = &code[..end];
+        let emoji = match emojis::get_by_shortcode(name) {
+            Some(emoji) => Some(Reaction::Emoji(EmojiToken::Unicode(
+                emoji.as_str().to_string(),
+            ))),
+            None => is_emoji_name(name).then(|| Reaction::Name(name.to_string())),
+        };
+
+        return (emoji.ok_or_else(|| token.to_string()), token.len());
+    }
+
+    let grapheme = text.graphemes(true).next().unwrap_or(text);
+
+    if let Some(emoji) = find_unicode(grapheme) {
+        return (
+            Ok(Reaction::Emoji(EmojiToken::Unicode(
+                emoji.as_str().to_string(),
+            ))),
+            grapheme.len(),
+        );
+    }
+
+    // anything else up to the next emoji or whitespace is rejected as a single token
+    let end = text
+        .grapheme_indices(true)
+        .skip(1)
+        .find(|(_, g)| {
+            g.starts_with(|c: char| c.is_whitespace() || c == '<' || c == ':')
+                || find_unicode(g).is_some()
+        })
+        .map_or(text.len(), |(index, _)| index);
+
+    (Err(text[..end].to_string()), end)
+}
+
+pub fn tokenize(text: &str) -> Tokenized {
+    let mut tokenized = Tokenized::default();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+        let (token, length) = next_token(&rest[start..]);
+
+        rest = &rest[start + length..];
+
+        match token {
+            Ok(emoji) if !tokenized.emojis.iter().any(|e| e.is_same(&emoji)) => {
+                tokenized.emojis.push(emoji);
+            }
+            Ok(_) => {}
+            Err(token) => tokenized.rejected.push(token),
+        }
+    }
+
+    tokenized
+}
+
+// the cache is kept current through the emoji intent, so the api is only asked on a miss
+pub async fn guild_emojis(ctx: &Context, guild: GuildId) -> Result<Vec<Emoji>> {
+    let cached = ctx
+        .cache
+        .guild(guild)
+        .map(|guild| guild.emojis.values().cloned().collect());
+
+    match cached {
+        Some(emojis) => Ok(emojis),
+        None => Ok(guild.emojis(ctx).await?),
+    }
+}
+
+// guild emojis referenced only by name or id are looked up when reacting
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reaction {
+    Emoji(EmojiToken),
+    Id(EmojiId),
+    Name(String),
+}
+
+fn is_emoji_name(name: &str) -> bool {
+    (2..=32).contains(&name.len()) && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+impl Reaction {
+    pub const fn is_guild_emoji(&self) -> bool {
+        !matches!(self, Self::Emoji(_))
+    }
+
+    pub const fn id(&self) -> Option<EmojiId> {
+        match self {
+            Self::Emoji(token) => token.id(),
+            Self::Id(id) => Some(*id),
+            Self::Name(_) => None,
+        }
+    }
+
+    // custom emojis are the same emoji whatever name they were written with
+    pub fn is_same(&self, other: &Self) -> bool {
+        match (self.id(), other.id()) {
+            (Some(a), Some(b)) => a == b,
+            _ => self == other,
+        }
+    }
+
+    pub fn resolve_in(&self, available: &[Emoji]) -> Option<EmojiToken> {
+        let found = match self {
+            Self::Emoji(token) => return Some(token.clone()),
+            Self::Id(id) => available.iter().find(|e| e.id == *id),
+            Self::Name(name) => available.iter().find(|e| &e.name == name),
+        }?;
+
+        Some(EmojiToken::Custom {
+            id: found.id,
+            name: found.name.clone(),
+            animated: found.animated,
+        })
+    }
+
+    pub async fn resolve(&self, ctx: &Context, guild: Option<GuildId>) -> Result<ReactionType> {
+        let available = match (self, guild) {
+            (Self::Emoji(_), _) => vec![],
+            (_, Some(guild)) => guild_emojis(ctx, guild).await?,
+            (_, None) => return Err(anyhow!("guild emoji {self} used outside of a guild")),
+        };
+
+        self.resolve_in(&available)
+            .map(Into::into)
+            .ok_or_else(|| anyhow!("guild emoji {self} does not exist"))
+    }
+}
+
+impl FromStr for Reaction {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let text = text.trim();
+
+        if let Some(id) = text.parse::<u64>().ok().filter(|id| *id != 0) {
+            return Ok(Self::Id(EmojiId::new(id)));
+        }
+
+        match next_token(text) {
+            (Ok(reaction), length) if length == text.len() => Ok(reaction),
+            _ => Err(anyhow!("\"{text}\" is not a single emoji!")),
+        }
+    }
+}
+
+impl Display for Reaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Emoji(token) => write!(f, "{token}"),
+            Self::Id(id) => write!(f, "{id}"),
+            Self::Name(name) => write!(f, ":{name}:"),
+        }
+    }
+}
+
+impl Serialize for Reaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+// older response files store every reaction as a single character
+impl<'de> Deserialize<'de> for Reaction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ReactionVisitor;
+
+        impl<'de> Visitor<'de> for ReactionVisitor {
+            type Value = Reaction;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an emoji, shortcode, custom emoji or emoji id")
+            }
+
+            fn visit_char<E: de::Error>(self, v: char) -> Result<Self::Value, E> {
+                self.visit_str(v.encode_utf8(&mut [0; 4]))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+        }
+
+        deserializer.deserialize_any(ReactionVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unicode(emoji: &str) -> Reaction {
+        Reaction::Emoji(EmojiToken::Unicode(emoji.to_string()))
+    }
+
+    fn custom(id: u64, name: &str, animated: bool) -> Reaction {
+        Reaction::Emoji(EmojiToken::Custom {
+            id: EmojiId::new(id),
+            name: name.to_string(),
+            animated,
+        })
+    }
+
+    #[test]
+    fn unspaced_sequences() {
+        let tokenized = tokenize("🙈🙉🙊");
+
+        assert_eq!(
+            tokenized.emojis,
+            [unicode("🙈"), unicode("🙉"), unicode("🙊")]
+        );
+        assert!(tokenized.rejected.is_empty());
+    }
+
+    #[test]
+    fn zwj_sequences_and_skin_tones() {
+        let tokenized = tokenize("👨\u{200d}👩\u{200d}👧👍🏽 👍");
+
+        assert_eq!(
+            tokenized.emojis,
+            [
+                unicode("👨\u{200d}👩\u{200d}👧"),
+                unicode("👍🏽"),
+                unicode("👍")
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_emojis() {
+        let tokenized = tokenize("<a:dance:123><:ape:456>");
+
+        assert_eq!(
+            tokenized.emojis,
+            [custom(123, "dance", true), custom(456, "ape", false)]
+        );
+    }
+
+    #[test]
+    fn shortcodes_and_names() {
+        let tokenized = tokenize(":monkey: :ape_guild:");
+
+        assert_eq!(
+            tokenized.emojis,
+            [unicode("🐒"), Reaction::Name("ape_guild".to_string())]
+        );
+    }
+
+    #[test]
+    fn rejected_tokens() {
+        let tokenized = tokenize("ook🙈 <:bad> <:zero:0> :unclosed");
+
+        assert_eq!(tokenized.emojis, [unicode("🙈")]);
+        assert_eq!(
+            tokenized.rejected,
+            ["ook", "<:bad>", "<:zero:0>", ":unclosed"]
+        );
+    }
+
+    #[test]
+    fn global_dedupe() {
+        let tokenized = tokenize("🙈 <:ape:456> 🙉🙈 <:renamed:456> :ape: :ape:");
+
+        assert_eq!(
+            tokenized.emojis,
+            [
+                unicode("🙈"),
+                custom(456, "ape", false),
+                unicode("🙉"),
+                Reaction::Name("ape".to_string())
+            ]
+        );
+    }
+}
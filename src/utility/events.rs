@@ -1,15 +1,36 @@
-use serenity::{all::OnlineStatus, prelude::EventHandler};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serenity::{
+    all::{MessageUpdateEvent, OnlineStatus},
+    prelude::EventHandler,
+};
 
 use crate::prelude::*;
 
+// link embeds usually arrive within seconds, though edits may add more well after
+const EMBED_MEMORY: Duration = Duration::from_secs(60 * 60);
+
 #[derive(Debug)]
 pub struct Events {
+    pub cache: Cache,
+    pub cooldowns: Cooldowns,
     pub logger: Logger,
+    searched: Mutex<BTreeMap<MessageId, (Instant, Vec<Embed>)>>,
 }
 
 impl Events {
-    pub const fn new(logger: Logger) -> Self {
-        Self { logger }
+    pub fn new(logger: Logger) -> Result<Self> {
+        let cache = Cache::new(logger.clone())?;
+
+        Ok(Self {
+            cache,
+            cooldowns: Cooldowns::default(),
+            logger,
+            searched: Mutex::default(),
+        })
     }
 
     pub fn info(&self, content: impl Into<String>) {
@@ -24,7 +45,7 @@ impl Events {
 
     pub async fn patch_commands(&self, http: &Http) -> Result<()> {
         let guild_id = dev_guild()?;
-        let cmds = vec![react::new(), speak::new()];
+        let cmds = COMMANDS.iter().map(|c| c.create()).collect::<Vec<_>>();
 
         let global = if DEV_BUILD {
             http.get_global_application_commands().await?.len()
@@ -38,49 +59,299 @@ impl Events {
 
         self.logger.info(format!("Patched {guild} guild commands"))
     }
-    pub async fn search_react(&self, http: &Http, message: &Message) -> Result<()> {
+    fn is_opted_out(&self, message: &Message) -> Result<bool> {
+        let author = &message.author.id;
+        let global =
+            self.cache
+                .get_opt::<BTreeSet<UserId>>(None, optout::DIR, optout::KEY, Kind::Ron)?;
+
+        if global
+            .as_ref()
+            .as_ref()
+            .is_some_and(|users| users.contains(author))
+        {
+            return Ok(true);
+        }
+
+        let Some(guild) = message.guild_id else {
+            return Ok(false);
+        };
+        let local = self.cache.get_opt::<BTreeSet<UserId>>(
+            Some(guild),
+            optout::DIR,
+            optout::KEY,
+            Kind::Ron,
+        )?;
+
+        Ok(local
+            .as_ref()
+            .as_ref()
+            .is_some_and(|users| users.contains(author)))
+    }
+    fn is_enabled(&self, dir: &str, message: &Message, channels: &[ChannelId]) -> Result<bool> {
+        let Some(guild) = message.guild_id else {
+            return Ok(true);
+        };
+
+        let access = self
+            .cache
+            .get_scoped::<Access>(Some(guild), dir, Access::KEY, Kind::Ron)?;
+        let roles = message.member.as_ref().map_or(&[][..], |m| &m.roles[..]);
+
+        Ok(access.is_empty() || access.permits(channels, roles))
+    }
+    // serenity keeps no messages, so the embeds searched so far are remembered for a while
+    fn remember_embeds(&self, message: &Message) -> Result<()> {
+        self.searched
+            .lock()
+            .map_err(|_| anyhow!("embed lock poisoned"))?
+            .insert(message.id, (Instant::now(), message.embeds.clone()));
+
+        Ok(())
+    }
+    fn added_embeds(&self, id: MessageId, embeds: Vec<Embed>) -> Result<Vec<Embed>> {
+        let now = Instant::now();
+        let mut searched = self
+            .searched
+            .lock()
+            .map_err(|_| anyhow!("embed lock poisoned"))?;
+
+        searched.retain(|_, (at, _)| now.duration_since(*at) < EMBED_MEMORY);
+
+        // older messages can't be told apart from ones that were merely pinned or re-embedded
+        let Some((_, known)) = searched.get_mut(&id) else {
+            return Ok(vec![]);
+        };
+        let added = embeds
+            .into_iter()
+            .filter(|embed| !known.iter().any(|k| is_same_embed(k, embed)))
+            .collect::<Vec<_>>();
+
+        known.extend(added.iter().cloned());
+        Ok(added)
+    }
+    fn on_cooldown(&self, dir: &str, message: &Message, word: &str) -> Result<bool> {
+        let guild = message.guild_id;
+        let config = self
+            .cache
+            .get_scoped::<Cooldown>(guild, dir, Cooldown::KEY, Kind::Ron)?;
+
+        let Some(scope) = self.cooldowns.try_start(dir, message, word, &config)? else {
+            return Ok(false);
+        };
+
+        self.info(format!(
+            "Skipping {dir} to \"{word}\": {scope} is on cooldown"
+        ));
+        Ok(true)
+    }
+    pub async fn search_react(
+        &self,
+        ctx: &Context,
+        message: &Message,
+        target: &(impl Search + Sync + ?Sized),
+    ) -> Result<()> {
         if message.author.bot {
             return Ok(());
         }
 
-        let triggers = Stored::<Vec<String>>::read("react", "triggers", Kind::Ron)?;
+        let guild = message.guild_id;
+        let triggers =
+            self.cache
+                .get_layered::<TriggerSet>(guild, "react", "triggers", Kind::Ron)?;
 
-        if let Some(word) = message.search(triggers.to_vec(), true, true) {
-            let responses = Stored::<WeightVec<char>>::read("react", "responses", Kind::Ron)?;
-            let response = *responses.get().ok_or_else(|| anyhow!("no responses"))?;
+        let matches = target.search(&triggers);
 
-            self.info(format!("Reacting to \"{word}\" with '{response}'"));
+        if let Some(found) = matches
+            .first_per_trigger()
+            .into_iter()
+            .find(|m| m.trigger.roll())
+        {
+            let trigger = found.trigger;
+            let count = matches.count(trigger);
+
+            if self.on_cooldown("react", message, &trigger.text)? {
+                return Ok(());
+            }
 
-            message.react(http, response).await?;
+            let responses = self.cache.get_layered::<WeightVec<Reaction>>(
+                guild,
+                "react",
+                "responses",
+                Kind::Ron,
+            )?;
+            let response = responses
+                .get()
+                .ok_or_else(|| anyhow!("no responses"))?
+                .clone();
+
+            self.info(format!(
+                "Reacting to {found} (\"{trigger}\", {count} matches) with '{response}'"
+            ));
+
+            message
+                .react(ctx, response.resolve(ctx, guild).await?)
+                .await?;
         }
 
         Ok(())
     }
-    pub async fn search_reply(&self, http: &Http, message: &Message) -> Result<()> {
+    pub async fn search_reply(
+        &self,
+        ctx: &Context,
+        message: &Message,
+        target: &(impl Search + Sync + ?Sized),
+    ) -> Result<()> {
         if message.author.bot {
             return Ok(());
         }
 
-        let triggers = Stored::<Vec<String>>::read("reply", "triggers", Kind::Ron)?;
+        let guild = message.guild_id;
+        let triggers =
+            self.cache
+                .get_layered::<TriggerSet>(guild, "reply", "triggers", Kind::Ron)?;
+
+        let matches = target.search(&triggers);
+
+        if let Some(found) = matches
+            .first_per_trigger()
+            .into_iter()
+            .find(|m| m.trigger.roll())
+        {
+            let trigger = found.trigger;
+            let count = matches.count(trigger);
 
-        if let Some(word) = message.search(triggers.to_vec(), true, true) {
-            let responses =
-                Stored::<WeightVec<(String, Option<char>)>>::read("reply", "responses", Kind::Ron)?;
-            let (text, emoji) = responses.get().ok_or_else(|| anyhow!("no responses"))?;
+            if self.on_cooldown("reply", message, &trigger.text)? {
+                return Ok(());
+            }
+
+            let responses = self
+                .cache
+                .get_layered::<WeightVec<(Template, Option<char>)>>(
+                    guild,
+                    "reply",
+                    "responses",
+                    Kind::Ron,
+                )?;
+            let (template, emoji) = responses
+                .get()
+                .ok_or_else(|| anyhow!("no responses"))?
+                .clone();
+            let guild_name = match guild {
+                Some(guild) if template.uses(Placeholder::Guild) => {
+                    let cached = ctx.cache.guild(guild).map(|guild| guild.name.clone());
+
+                    match cached {
+                        Some(name) => Some(name),
+                        None => Some(guild.to_partial_guild(ctx).await?.name),
+                    }
+                }
+                _ => None,
+            };
+            let context = TemplateContext {
+                author: message
+                    .member
+                    .as_ref()
+                    .and_then(|member| member.nick.clone())
+                    .or_else(|| message.author.global_name.as_ref().map(ToString::to_string))
+                    .unwrap_or_else(|| message.author.name.clone()),
+                author_id: message.author.id,
+                channel_id: message.channel_id,
+                guild: guild_name,
+                // the matched text is what the author wrote, rather than the stored trigger
+                trigger: found.text.clone(),
+            };
+            let text = template.render(&context);
             let emoji = emoji.map(|c| c.to_string()).unwrap_or_default();
             let response = format!("{emoji} {text} {emoji}").trim().to_string();
 
-            self.info(format!("Replying to \"{word}\" with \"{response}\""));
+            self.info(format!(
+                "Replying to {found} (\"{trigger}\", {count} matches) with \"{response}\""
+            ));
 
+            // only the author may be pinged, whatever a rendered name happens to contain
+            let mentions = CreateAllowedMentions::new()
+                .users(vec![message.author.id])
+                .replied_user(true);
             let reply = CreateMessage::new()
                 .reference_message(message)
+                .allowed_mentions(mentions)
                 .content(response);
 
-            message.channel_id.send_message(http, reply).await?;
+            message.channel_id.send_message(ctx, reply).await?;
         }
 
         Ok(())
     }
+    // the target is what gets searched, which is only part of the message after an update
+    pub async fn respond(
+        &self,
+        ctx: &Context,
+        message: &Message,
+        target: &(impl Search + Sync + ?Sized),
+    ) {
+        match self.is_opted_out(message) {
+            Ok(false) => {}
+            Ok(true) => return,
+            Err(error) => return self.warn(format!("Error checking opt-outs: {error}")),
+        }
+
+        let channels = match channel_chain(ctx, message).await {
+            Ok(channels) => channels,
+            Err(error) => return self.warn(format!("Error resolving channels: {error}")),
+        };
+
+        match self.is_enabled("react", message, &channels) {
+            Ok(false) => {}
+            Ok(true) => {
+                if let Err(error) = self.search_react(ctx, message, target).await {
+                    self.warn(format!("Error reacting: {error}"));
+                }
+            }
+            Err(error) => self.warn(format!("Error checking react access: {error}")),
+        }
+        match self.is_enabled("reply", message, &channels) {
+            Ok(false) => {}
+            Ok(true) => {
+                if let Err(error) = self.search_reply(ctx, message, target).await {
+                    self.warn(format!("Error replying: {error}"));
+                }
+            }
+            Err(error) => self.warn(format!("Error checking reply access: {error}")),
+        }
+    }
+}
+
+// threads sit within a channel, which may itself sit within a category
+async fn channel_chain(ctx: &Context, message: &Message) -> Result<Vec<ChannelId>> {
+    let mut channels = vec![message.channel_id];
+
+    if message.guild_id.is_none() {
+        return Ok(channels);
+    }
+
+    while channels.len() < 3 {
+        let id = channels[channels.len() - 1];
+        let cached = ctx.cache.guild_channel(id).map(|channel| channel.parent_id);
+        let parent = match cached {
+            Some(parent) => parent,
+            None => match id.to_channel(ctx).await? {
+                Channel::Guild(channel) => channel.parent_id,
+                _ => None,
+            },
+        };
+        let Some(parent) = parent else {
+            break;
+        };
+
+        channels.push(parent);
+    }
+
+    Ok(channels)
+}
+
+fn is_same_embed(a: &Embed, b: &Embed) -> bool {
+    a.url == b.url && a.title == b.title && a.description == b.description
 }
 
 #[async_trait]
@@ -102,14 +373,40 @@ impl EventHandler for Events {
         }
     }
     async fn message(&self, ctx: Context, message: Message) {
-        if let Err(error) = self.search_react(&ctx.http, &message).await {
-            self.warn(format!("Error reacting: {error}"));
-        }
-        if let Err(error) = self.search_reply(&ctx.http, &message).await {
-            self.warn(format!("Error replying: {error}"));
+        if let Err(error) = self.remember_embeds(&message) {
+            self.warn(format!("Error remembering embeds: {error}"));
         }
+
+        self.respond(&ctx, &message, &message).await;
     }
-    async fn interaction_create(&self, ctx: Context, mut interaction: Interaction) {
+    // link embeds arrive in an update of their own, so only the embeds not yet searched are
+    async fn message_update(
+        &self,
+        ctx: Context,
+        _: Option<Message>,
+        new: Option<Message>,
+        event: MessageUpdateEvent,
+    ) {
+        let Some(embeds) = event.embeds else {
+            return;
+        };
+        let added = match self.added_embeds(event.id, embeds) {
+            Ok(added) if added.is_empty() => return,
+            Ok(added) => added,
+            Err(error) => return self.warn(format!("Error checking embeds: {error}")),
+        };
+
+        let message = match new {
+            Some(message) => message,
+            None => match event.channel_id.message(&ctx, event.id).await {
+                Ok(message) => message,
+                Err(error) => return self.warn(format!("Error fetching message: {error}")),
+            },
+        };
+
+        self.respond(&ctx, &message, &added[..]).await;
+    }
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         let id = match &interaction {
             Interaction::Autocomplete(i) => format!("{}<a:{}>", i.data.name, i.id),
             Interaction::Command(i) => format!("{}<c:{}>", i.data.name, i.id),
@@ -120,11 +417,24 @@ impl EventHandler for Events {
 
         let http = &ctx.http;
 
-        let result = match &mut interaction {
-            Interaction::Command(command) => match command.data.name.as_str() {
-                react::NAME => react::run_command(http, command).await,
-                speak::NAME => speak::run_command(http, command).await,
-                _ => Err(anyhow!("unknown interaction: {id}")),
+        let result = match &interaction {
+            Interaction::Autocomplete(autocomplete) => {
+                match find_command(&autocomplete.data.name) {
+                    Ok(handler) => handler.run_autocomplete(&ctx, autocomplete).await,
+                    Err(error) => Err(error),
+                }
+            }
+            Interaction::Command(command) => match find_command(&command.data.name) {
+                Ok(handler) => handler.run_command(&ctx, command).await,
+                Err(error) => Err(error),
+            },
+            Interaction::Component(component) => match find_handler(&component.data.custom_id) {
+                Ok((handler, id)) => handler.run_component(&ctx, component, id).await,
+                Err(error) => Err(error),
+            },
+            Interaction::Modal(modal) => match find_handler(&modal.data.custom_id) {
+                Ok((handler, id)) => handler.run_modal(&ctx, modal, id).await,
+                Err(error) => Err(error),
             },
             _ => Err(anyhow!("unknown interaction: {id}")),
         };
@@ -137,14 +447,32 @@ impl EventHandler for Events {
                 .description(format!("> {error}"))
                 .title("Encountered an error!");
             let r = CreateInteractionResponseFollowup::new()
-                .embed(embed)
+                .embed(embed.clone())
                 .ephemeral(true);
+            // interactions may fail before they're acknowledged, such as unknown commands
+            let m = CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            );
 
             let result = match &interaction {
-                Interaction::Command(i) => i.create_followup(http, r).await.map_err(Into::into),
-                Interaction::Component(i) => i.create_followup(http, r).await.map_err(Into::into),
-                Interaction::Modal(i) => i.create_followup(http, r).await.map_err(Into::into),
-                _ => Err(anyhow!("invalid interaction type")),
+                Interaction::Command(i) => match i.create_followup(http, r).await {
+                    Ok(_) => Ok(()),
+                    Err(_) => i.create_response(http, m).await,
+                },
+                Interaction::Component(i) => match i.create_followup(http, r).await {
+                    Ok(_) => Ok(()),
+                    Err(_) => i.create_response(http, m).await,
+                },
+                Interaction::Modal(i) => match i.create_followup(http, r).await {
+                    Ok(_) => Ok(()),
+                    Err(_) => i.create_response(http, m).await,
+                },
+                _ => {
+                    self.warn("Error could not be displayed: invalid interaction type");
+                    return;
+                }
             };
 
             if let Err(error) = result {
@@ -4,11 +4,29 @@ use rand::{thread_rng, Rng};
 
 use crate::prelude::*;
 
+pub fn chance(probability: f64) -> bool {
+    probability >= 1.0 || (probability > 0.0 && thread_rng().gen_bool(probability))
+}
+
 #[repr(transparent)]
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct WeightVec<T>(Vec<(T, f64)>);
 
 impl<T> WeightVec<T> {
+    pub const fn new() -> Self {
+        Self(vec![])
+    }
+
+    pub fn push(&mut self, value: T, weight: f64) {
+        self.0.push((value, weight));
+    }
+    pub fn remove(&mut self, index: usize) -> Option<(T, f64)> {
+        (index < self.0.len()).then(|| self.0.remove(index))
+    }
+    pub fn total(&self) -> f64 {
+        self.iter().map(|(_, w)| *w).sum()
+    }
+
     pub fn get(&self) -> Option<&T> {
         let weights = self.iter().map(|(_, w)| *w).enumerate().fold(
             Vec::with_capacity(self.0.len()),
@@ -27,6 +45,15 @@ impl<T> WeightVec<T> {
     }
 }
 
+impl<T> Merge for WeightVec<T>
+where
+    T: Clone,
+{
+    fn merge(&mut self, other: &Self) {
+        self.0.extend_from_slice(other);
+    }
+}
+
 impl<T> Deref for WeightVec<T> {
     type Target = [(T, f64)];
 
@@ -1,54 +1,707 @@
+use std::{
+    borrow::Cow,
+    ops::{Deref, Range},
+};
+
+use aho_corasick::AhoCorasick;
+use regex::{Regex, RegexBuilder};
+use serde::{
+    de::{
+        self,
+        value::{MapAccessDeserializer, SeqAccessDeserializer},
+        MapAccess, SeqAccess, Visitor,
+    },
+    ser::SerializeStruct,
+    Deserializer, Serializer,
+};
+
 use crate::prelude::*;
 
-pub trait Search {
-    fn search_contains(&self, target: &str, any_case: bool, skip_spaces: bool) -> bool;
+mod fuzzy;
+mod normalize;
 
-    fn search(&self, targets: Vec<String>, any_case: bool, skip_spaces: bool) -> Option<String> {
-        targets
-            .into_iter()
-            .find(|target| self.search_contains(target, any_case, skip_spaces))
+pub use fuzzy::*;
+pub use normalize::*;
+
+const fn default_chance() -> f64 {
+    1.0
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mode {
+    #[default]
+    Substring,
+    Word,
+    Regex,
+}
+
+impl Mode {
+    pub const ALL: [Self; 3] = [Self::Substring, Self::Word, Self::Regex];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Substring => "substring",
+            Self::Word => "word",
+            Self::Regex => "regex",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TriggerDef {
+    text: String,
+    #[serde(default = "default_chance")]
+    chance: f64,
+    #[serde(default)]
+    mode: Mode,
+    #[serde(default)]
+    fuzzy: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Trigger {
+    pub text: String,
+    pub chance: f64,
+    pub mode: Mode,
+    pub fuzzy: bool,
+    #[serde(skip)]
+    regex: Option<Regex>,
+}
+
+impl Trigger {
+    pub fn new(text: impl Into<String>, chance: f64, mode: Mode, fuzzy: bool) -> Result<Self> {
+        Self::try_from(TriggerDef {
+            text: text.into(),
+            chance,
+            mode,
+            fuzzy,
+        })
+    }
+
+    pub fn roll(&self) -> bool {
+        chance(self.chance)
+    }
+    pub const fn regex(&self) -> Option<&Regex> {
+        self.regex.as_ref()
+    }
+}
+
+impl PartialEq for Trigger {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+            && self.chance == other.chance
+            && self.mode == other.mode
+            && self.fuzzy == other.fuzzy
+    }
+}
+
+impl TryFrom<TriggerDef> for Trigger {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        TriggerDef {
+            text,
+            chance,
+            mode,
+            fuzzy,
+        }: TriggerDef,
+    ) -> Result<Self> {
+        if text.trim().is_empty() {
+            return Err(anyhow!("triggers cannot be empty"));
+        }
+        if !(0.0..=1.0).contains(&chance) {
+            return Err(anyhow!("trigger chance must be between 0 and 1"));
+        }
+
+        let regex = match mode {
+            Mode::Regex => Some(
+                RegexBuilder::new(&text)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|error| anyhow!("invalid trigger regex \"{text}\": {error}"))?,
+            ),
+            _ => None,
+        };
+
+        Ok(Self {
+            text,
+            chance,
+            mode,
+            fuzzy,
+            regex,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Trigger {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TriggerVisitor;
+
+        impl<'de> Visitor<'de> for TriggerVisitor {
+            type Value = Trigger;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a trigger string or structure")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Trigger::new(v, default_chance(), Mode::default(), false).map_err(E::custom)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let def = TriggerDef::deserialize(MapAccessDeserializer::new(map))?;
+
+                Trigger::try_from(def).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(TriggerVisitor)
     }
 }
 
-pub fn str_contains(source: &str, target: &str, any_case: bool, skip_spaces: bool) -> bool {
-    let (mut source, mut target) = if any_case {
-        (source.to_lowercase(), target.to_lowercase())
-    } else {
-        (source.to_string(), target.to_string())
-    };
-    if skip_spaces {
-        source = source.replace([' ', '\t', '\n', '\r'], "");
-        target = target.replace([' ', '\t', '\n', '\r'], "");
+impl Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
     }
+}
 
-    source.contains(&target)
+#[derive(Clone, Debug, Default)]
+struct Automaton {
+    inner: Option<AhoCorasick>,
+    ids: Vec<usize>,
+}
+
+impl Automaton {
+    fn new(triggers: &[Trigger], mode: Mode, normalize: &Normalize) -> Self {
+        let (ids, patterns): (Vec<_>, Vec<_>) = triggers
+            .iter()
+            .enumerate()
+            .filter(|(_, trigger)| trigger.mode == mode)
+            .map(|(index, trigger)| (index, normalize.apply(&trigger.text, mode)))
+            .filter(|(_, pattern)| !pattern.is_empty())
+            .unzip();
+
+        let inner = (!patterns.is_empty()).then(|| AhoCorasick::new(&patterns));
+
+        Self { inner, ids }
+    }
+
+    fn find(&self, haystack: &str) -> Vec<(usize, Range<usize>)> {
+        self.inner.as_ref().map_or_else(Vec::new, |inner| {
+            inner
+                .find_overlapping_iter(haystack)
+                .map(|m| (self.ids[m.pattern()], m.start()..m.end()))
+                .collect()
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct TriggerSetDef {
+    #[serde(default)]
+    normalize: Normalize,
+    #[serde(default)]
+    fuzzy: Fuzzy,
+    #[serde(default)]
+    sources: Sources,
+    #[serde(default)]
+    exclude: Vec<Exclusion>,
+    triggers: Vec<Trigger>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Exclusion {
+    pub pattern: Trigger,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<String>,
+    #[serde(default)]
+    pub anywhere: bool,
+}
+
+impl Exclusion {
+    fn suppresses(&self, found: &Match<'_>, span: &Range<usize>) -> bool {
+        let applies = self.triggers.is_empty() || self.triggers.contains(&found.trigger.text);
+        let overlaps = span.start < found.span.end && found.span.start < span.end;
+
+        applies && (self.anywhere || overlaps)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TriggerSet {
+    normalize: Normalize,
+    fuzzy: Fuzzy,
+    sources: Sources,
+    exclude: Vec<Exclusion>,
+    excluder: Option<Box<TriggerSet>>,
+    triggers: Vec<Trigger>,
+    substring: Automaton,
+    word: Automaton,
+    approximate: Vec<(usize, Mode, String, usize)>,
+}
+
+impl TriggerSet {
+    pub fn new(
+        normalize: Normalize,
+        fuzzy: Fuzzy,
+        sources: Sources,
+        exclude: Vec<Exclusion>,
+        triggers: Vec<Trigger>,
+    ) -> Self {
+        let substring = Automaton::new(&triggers, Mode::Substring, &normalize);
+        let word = Automaton::new(&triggers, Mode::Word, &normalize);
+        let approximate = triggers
+            .iter()
+            .enumerate()
+            .filter(|(_, trigger)| trigger.fuzzy && trigger.mode != Mode::Regex)
+            .filter_map(|(index, trigger)| {
+                let pattern = normalize.apply(&trigger.text, trigger.mode);
+                let distance = fuzzy.distance_for(&pattern);
+
+                (distance > 0).then_some((index, trigger.mode, pattern, distance))
+            })
+            .collect();
+        let excluder = (!exclude.is_empty()).then(|| {
+            let patterns = exclude.iter().map(|e| e.pattern.clone()).collect();
+
+            Box::new(Self::new(
+                normalize.clone(),
+                fuzzy,
+                Sources::default(),
+                vec![],
+                patterns,
+            ))
+        });
+
+        Self {
+            normalize,
+            fuzzy,
+            sources,
+            exclude,
+            excluder,
+            triggers,
+            substring,
+            word,
+            approximate,
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let triggers = std::mem::take(&mut self.triggers);
+        let normalize = std::mem::take(&mut self.normalize);
+        let exclude = std::mem::take(&mut self.exclude);
+
+        *self = Self::new(normalize, self.fuzzy, self.sources, exclude, triggers);
+    }
+
+    pub const fn sources(&self) -> &Sources {
+        &self.sources
+    }
+
+    pub fn push(&mut self, trigger: Trigger) {
+        self.triggers.push(trigger);
+        self.rebuild();
+    }
+    pub fn retain(&mut self, f: impl FnMut(&Trigger) -> bool) {
+        self.triggers.retain(f);
+        self.rebuild();
+    }
+
+    pub fn find(&self, source: Source, text: &str) -> Vec<Match<'_>> {
+        let mut found = BTreeMap::<usize, Vec<(Range<usize>, usize)>>::new();
+
+        for (mode, automaton) in [(Mode::Substring, &self.substring), (Mode::Word, &self.word)] {
+            if automaton.inner.is_none() {
+                continue;
+            }
+
+            let haystack = self.normalize.apply_mapped(text, mode);
+            let accept = |range: &Range<usize>| {
+                mode != Mode::Word || is_word_boundary(&haystack.text, range)
+            };
+
+            for (index, range) in automaton.find(&haystack.text) {
+                if accept(&range) {
+                    found
+                        .entry(index)
+                        .or_default()
+                        .push((haystack.origin_of(&range), 0));
+                }
+            }
+
+            for (index, _, pattern, distance) in self.approximate.iter().filter(|a| a.1 == mode) {
+                if found.contains_key(index) {
+                    continue;
+                }
+                if let Some((range, distance)) =
+                    Fuzzy::find(&haystack.text, pattern, *distance, accept)
+                {
+                    found.insert(*index, vec![(haystack.origin_of(&range), distance)]);
+                }
+            }
+        }
+
+        // regular expressions can't be normalized, so only the message text is
+        let haystack = (self.normalize != Normalize::default())
+            .then(|| self.normalize.apply_mapped(text, Mode::Regex));
+
+        for (index, trigger) in self.triggers.iter().enumerate() {
+            let Some(regex) = trigger.regex() else {
+                continue;
+            };
+
+            let spans = match &haystack {
+                Some(haystack) => regex
+                    .find_iter(&haystack.text)
+                    .map(|m| haystack.origin_of(&m.range()))
+                    .collect::<Vec<_>>(),
+                None => regex.find_iter(text).map(|m| m.range()).collect(),
+            };
+
+            if !spans.is_empty() {
+                found
+                    .entry(index)
+                    .or_insert_with(|| spans.into_iter().map(|span| (span, 0)).collect());
+            }
+        }
+
+        let matches = found.into_iter().flat_map(|(index, spans)| {
+            spans.into_iter().map(move |(span, distance)| Match {
+                trigger: &self.triggers[index],
+                source,
+                text: text[span.clone()].to_string(),
+                span,
+                distance,
+            })
+        });
+
+        let Some(excluder) = &self.excluder else {
+            return matches.collect();
+        };
+
+        let excluded = excluder.find(source, text);
+
+        matches
+            .filter(|found| {
+                !excluded.iter().any(|e| {
+                    let index = excluder.iter().position(|t| std::ptr::eq(t, e.trigger));
+
+                    index.map_or(false, |index| {
+                        self.exclude[index].suppresses(found, &e.span)
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+impl Merge for TriggerSet {
+    fn merge(&mut self, other: &Self) {
+        self.triggers.merge(&other.triggers);
+        self.exclude.merge(&other.exclude);
+        self.rebuild();
+    }
+}
+
+impl Deref for TriggerSet {
+    type Target = [Trigger];
+
+    fn deref(&self) -> &Self::Target {
+        &self.triggers
+    }
+}
+
+impl Serialize for TriggerSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TriggerSet", 5)?;
+
+        state.serialize_field("normalize", &self.normalize)?;
+        state.serialize_field("fuzzy", &self.fuzzy)?;
+        state.serialize_field("sources", &self.sources)?;
+        state.serialize_field("exclude", &self.exclude)?;
+        state.serialize_field("triggers", &self.triggers)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for TriggerSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TriggerSetVisitor;
+
+        impl<'de> Visitor<'de> for TriggerSetVisitor {
+            type Value = TriggerSet;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a list of triggers or a trigger set structure")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                let triggers = Vec::deserialize(SeqAccessDeserializer::new(seq))?;
+
+                Ok(TriggerSet::new(
+                    Normalize::default(),
+                    Fuzzy::default(),
+                    Sources::default(),
+                    vec![],
+                    triggers,
+                ))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let def = TriggerSetDef::deserialize(MapAccessDeserializer::new(map))?;
+
+                Ok(TriggerSet::new(
+                    def.normalize,
+                    def.fuzzy,
+                    def.sources,
+                    def.exclude,
+                    def.triggers,
+                ))
+            }
+        }
+
+        deserializer.deserialize_any(TriggerSetVisitor)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Source {
+    Content,
+    Author,
+    AccentColour,
+    Member,
+    Embed,
+    Attachment,
+    Sticker,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Content => write!(f, "content"),
+            Self::Author => write!(f, "author name"),
+            Self::AccentColour => write!(f, "accent colour"),
+            Self::Member => write!(f, "member name"),
+            Self::Embed => write!(f, "embed"),
+            Self::Attachment => write!(f, "attachment name"),
+            Self::Sticker => write!(f, "sticker name"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sources {
+    pub content: bool,
+    pub author: bool,
+    pub accent_colour: bool,
+    pub member: bool,
+    pub embeds: bool,
+    pub attachments: bool,
+    pub stickers: bool,
+}
+
+// embeds, attachments and stickers must be enabled per set so existing sets keep their behaviour
+impl Default for Sources {
+    fn default() -> Self {
+        Self {
+            content: true,
+            author: true,
+            accent_colour: true,
+            member: true,
+            embeds: false,
+            attachments: false,
+            stickers: false,
+        }
+    }
+}
+
+impl Sources {
+    pub const fn contains(&self, source: Source) -> bool {
+        match source {
+            Source::Content => self.content,
+            Source::Author => self.author,
+            Source::AccentColour => self.accent_colour,
+            Source::Member => self.member,
+            Source::Embed => self.embeds,
+            Source::Attachment => self.attachments,
+            Source::Sticker => self.stickers,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match<'t> {
+    pub trigger: &'t Trigger,
+    pub source: Source,
+    pub text: String,
+    pub span: Range<usize>,
+    pub distance: usize,
+}
+
+impl Display for Match<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Range { start, end } = self.span;
+
+        write!(f, "\"{}\" in {} at {start}..{end}", self.text, self.source)?;
+
+        if self.distance > 0 {
+            write!(f, ", {} edits away", self.distance)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Matches<'t>(Vec<Match<'t>>);
+
+impl<'t> Matches<'t> {
+    pub fn count(&self, trigger: &Trigger) -> usize {
+        self.0
+            .iter()
+            .filter(|m| std::ptr::eq(m.trigger, trigger))
+            .count()
+    }
+
+    pub fn first_per_trigger(&self) -> Vec<&Match<'t>> {
+        let mut firsts: Vec<&Match> = vec![];
+
+        for found in &self.0 {
+            if !firsts
+                .iter()
+                .any(|m| std::ptr::eq(m.trigger, found.trigger))
+            {
+                firsts.push(found);
+            }
+        }
+
+        firsts
+    }
+}
+
+impl<'t> Deref for Matches<'t> {
+    type Target = [Match<'t>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+pub trait Search {
+    fn search_texts(&self) -> Vec<(Source, Cow<'_, str>)>;
+
+    fn search<'t>(&self, triggers: &'t TriggerSet) -> Matches<'t> {
+        Matches(
+            self.search_texts()
+                .into_iter()
+                .filter(|(source, _)| triggers.sources().contains(*source))
+                .flat_map(|(source, text)| triggers.find(source, &text))
+                .collect(),
+        )
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric()
+}
+
+fn is_word_boundary(haystack: &str, range: &Range<usize>) -> bool {
+    let before = haystack[..range.start].chars().next_back();
+    let after = haystack[range.end..].chars().next();
+
+    !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+}
+
+impl Search for str {
+    fn search_texts(&self) -> Vec<(Source, Cow<'_, str>)> {
+        vec![(Source::Content, Cow::Borrowed(self))]
+    }
 }
 
 impl Search for User {
-    fn search_contains(&self, target: &str, any_case: bool, skip_spaces: bool) -> bool {
-        str_contains(&self.face(), target, any_case, skip_spaces)
-            || self
-                .accent_colour
-                .map(Color::hex)
-                .is_some_and(|c| str_contains(&c, target, any_case, skip_spaces))
+    fn search_texts(&self) -> Vec<(Source, Cow<'_, str>)> {
+        let mut texts = vec![(Source::Author, Cow::Owned(self.face()))];
+
+        if let Some(color) = self.accent_colour {
+            texts.push((Source::AccentColour, Cow::Owned(color.hex())));
+        }
+
+        texts
     }
 }
 
 impl Search for PartialMember {
-    fn search_contains(&self, target: &str, any_case: bool, skip_spaces: bool) -> bool {
-        self.user
-            .as_ref()
-            .is_some_and(|user| user.search_contains(target, any_case, skip_spaces))
+    fn search_texts(&self) -> Vec<(Source, Cow<'_, str>)> {
+        self.user.as_ref().map_or_else(Vec::new, |user| {
+            user.search_texts()
+                .into_iter()
+                .map(|(_, text)| (Source::Member, text))
+                .collect()
+        })
     }
 }
 
+impl Search for Embed {
+    fn search_texts(&self) -> Vec<(Source, Cow<'_, str>)> {
+        let fields = self
+            .fields
+            .iter()
+            .flat_map(|field| [field.name.as_str(), field.value.as_str()]);
+
+        [self.title.as_deref(), self.description.as_deref()]
+            .into_iter()
+            .chain([
+                self.author.as_ref().map(|author| author.name.as_str()),
+                self.footer.as_ref().map(|footer| footer.text.as_str()),
+            ])
+            .flatten()
+            .chain(fields)
+            .map(|text| (Source::Embed, Cow::Borrowed(text)))
+            .collect()
+    }
+}
+
+impl Search for [Embed] {
+    fn search_texts(&self) -> Vec<(Source, Cow<'_, str>)> {
+        self.iter().flat_map(Embed::search_texts).collect()
+    }
+}
+
+impl Search for Attachment {
+    fn search_texts(&self) -> Vec<(Source, Cow<'_, str>)> {
+        vec![(Source::Attachment, Cow::Borrowed(self.filename.as_str()))]
+    }
+}
+
+impl Search for StickerItem {
+    fn search_texts(&self) -> Vec<(Source, Cow<'_, str>)> {
+        vec![(Source::Sticker, Cow::Borrowed(self.name.as_str()))]
+    }
+}
+
+// polls aren't modelled by the pinned serenity version, so they can't be searched yet
 impl Search for Message {
-    fn search_contains(&self, target: &str, any_case: bool, skip_spaces: bool) -> bool {
-        str_contains(&self.content, target, any_case, skip_spaces)
-            || self.author.search_contains(target, any_case, skip_spaces)
-            || self
-                .member
-                .as_ref()
-                .is_some_and(|m| m.search_contains(target, any_case, skip_spaces))
+    fn search_texts(&self) -> Vec<(Source, Cow<'_, str>)> {
+        let mut texts = vec![(Source::Content, Cow::Borrowed(self.content.as_str()))];
+
+        texts.extend(self.author.search_texts());
+
+        if let Some(member) = &self.member {
+            texts.extend(member.search_texts());
+        }
+
+        texts.extend(self.embeds.search_texts());
+        texts.extend(self.attachments.iter().flat_map(Attachment::search_texts));
+        texts.extend(
+            self.sticker_items
+                .iter()
+                .flat_map(StickerItem::search_texts),
+        );
+
+        texts
     }
 }
+
+#[cfg(test)]
+mod bench;
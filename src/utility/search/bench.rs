@@ -0,0 +1,87 @@
+extern crate test;
+
+use test::{black_box, Bencher};
+
+use super::*;
+
+const MESSAGE: &str = "Did you see the news? Apparently the zoo got a new resident, some \
+    kind of small primate that nobody can identify. The keepers think it might be a \
+    marmoset, but honestly it looks more like a tiny grumpy old man wearing a fur coat. \
+    Anyway, I'm heading over there this weekend if anyone wants to come along and see \
+    the little monke for themselves.";
+
+fn triggers(count: usize) -> Vec<Trigger> {
+    (0..count)
+        .filter_map(|index| {
+            let mode = if index % 4 == 0 {
+                Mode::Word
+            } else {
+                Mode::Substring
+            };
+
+            Trigger::new(format!("trigger{index:04}"), 1.0, mode, false).ok()
+        })
+        .chain(Trigger::new("monke", 1.0, Mode::Substring, false).ok())
+        .collect()
+}
+
+// the per-trigger approach the automaton replaced, kept as a baseline
+fn naive<'t>(source: &str, triggers: &'t [Trigger]) -> Vec<&'t Trigger> {
+    triggers
+        .iter()
+        .filter(|trigger| {
+            let source = Normalize::default().apply(source, trigger.mode);
+            let target = Normalize::default().apply(&trigger.text, trigger.mode);
+
+            match trigger.mode {
+                Mode::Word => source
+                    .match_indices(&target)
+                    .any(|(start, word)| is_word_boundary(&source, &(start..start + word.len()))),
+                _ => source.contains(&target),
+            }
+        })
+        .collect()
+}
+
+macro_rules! bench_pair {
+    ($naive:ident, $automaton:ident, $count:literal) => {
+        #[bench]
+        fn $naive(b: &mut Bencher) {
+            let triggers = triggers($count);
+
+            b.iter(|| naive(black_box(MESSAGE), &triggers));
+        }
+
+        #[bench]
+        fn $automaton(b: &mut Bencher) {
+            let triggers = TriggerSet::new(
+                Normalize::default(),
+                Fuzzy::default(),
+                Sources::default(),
+                vec![],
+                triggers($count),
+            );
+
+            b.iter(|| triggers.find(Source::Content, black_box(MESSAGE)));
+        }
+    };
+}
+
+bench_pair!(naive_10, automaton_10, 10);
+bench_pair!(naive_100, automaton_100, 100);
+bench_pair!(naive_1000, automaton_1000, 1000);
+
+#[bench]
+fn build_1000(b: &mut Bencher) {
+    let triggers = triggers(1000);
+
+    b.iter(|| {
+        TriggerSet::new(
+            Normalize::default(),
+            Fuzzy::default(),
+            Sources::default(),
+            vec![],
+            black_box(triggers.clone()),
+        )
+    });
+}
@@ -0,0 +1,67 @@
+use std::ops::Range;
+
+use strsim::osa_distance;
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fuzzy {
+    pub min_length: usize,
+    pub chars_per_edit: usize,
+    pub max_distance: usize,
+}
+
+impl Default for Fuzzy {
+    fn default() -> Self {
+        Self {
+            min_length: 5,
+            chars_per_edit: 4,
+            max_distance: 2,
+        }
+    }
+}
+
+impl Fuzzy {
+    pub fn distance_for(&self, pattern: &str) -> usize {
+        let length = pattern.chars().count();
+
+        if length < self.min_length || self.chars_per_edit == 0 {
+            0
+        } else {
+            (length / self.chars_per_edit).min(self.max_distance)
+        }
+    }
+
+    pub fn find(
+        haystack: &str,
+        pattern: &str,
+        distance: usize,
+        accept: impl Fn(&Range<usize>) -> bool,
+    ) -> Option<(Range<usize>, usize)> {
+        let chars = haystack.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+        let length = pattern.chars().count();
+        let widths = length.saturating_sub(distance).max(1)..=length + distance;
+        let mut best: Option<(Range<usize>, usize)> = None;
+
+        for start in 0..chars.len() {
+            for width in widths.clone() {
+                let Some(end) = start.checked_add(width).filter(|end| *end <= chars.len()) else {
+                    break;
+                };
+
+                let range = chars[start]..chars.get(end).copied().unwrap_or(haystack.len());
+                let found = osa_distance(&haystack[range.clone()], pattern);
+
+                if found <= distance
+                    && best.as_ref().map_or(true, |(_, best)| found < *best)
+                    && accept(&range)
+                {
+                    best = Some((range, found));
+                }
+            }
+        }
+
+        best
+    }
+}
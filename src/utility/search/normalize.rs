@@ -0,0 +1,243 @@
+use std::ops::Range;
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::prelude::*;
+
+// these are ordinary digits and punctuation outside of a word, so they're only folded beside one
+const LEETSPEAK: &[(char, char)] = &[
+    ('0', 'o'),
+    ('1', 'i'),
+    ('3', 'e'),
+    ('4', 'a'),
+    ('5', 's'),
+    ('7', 't'),
+    ('@', 'a'),
+    ('$', 's'),
+    ('!', 'i'),
+];
+
+const HOMOGLYPHS: &[(char, char)] = &[
+    // cyrillic
+    ('а', 'a'),
+    ('в', 'b'),
+    ('е', 'e'),
+    ('к', 'k'),
+    ('м', 'm'),
+    ('н', 'h'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('с', 'c'),
+    ('т', 't'),
+    ('у', 'y'),
+    ('х', 'x'),
+    ('ѕ', 's'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('ԁ', 'd'),
+    ('ӏ', 'l'),
+    // greek
+    ('α', 'a'),
+    ('ε', 'e'),
+    ('ι', 'i'),
+    ('κ', 'k'),
+    ('ν', 'v'),
+    ('ο', 'o'),
+    ('ρ', 'p'),
+    ('τ', 't'),
+    ('υ', 'u'),
+    ('χ', 'x'),
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Normalize {
+    pub nfkc: bool,
+    pub diacritics: bool,
+    pub homoglyphs: bool,
+    pub fold: BTreeMap<char, char>,
+    pub collapse: bool,
+}
+
+impl Normalize {
+    fn fold_char(&self, c: char, neighbours: [Option<char>; 2]) -> char {
+        if let Some(folded) = self.fold.get(&c) {
+            return *folded;
+        }
+        if !self.homoglyphs {
+            return c;
+        }
+
+        // digits read as letters beside any letter, while punctuation must sit within a word
+        let is_leetspeak = if c.is_ascii_digit() {
+            neighbours.iter().flatten().any(|n| n.is_alphabetic())
+        } else {
+            neighbours
+                .iter()
+                .all(|n| n.is_some_and(char::is_alphanumeric))
+        };
+        let leetspeak: &[(char, char)] = if is_leetspeak { LEETSPEAK } else { &[] };
+
+        HOMOGLYPHS
+            .iter()
+            .chain(leetspeak)
+            .find_map(|(from, to)| (*from == c).then_some(*to))
+            .unwrap_or(c)
+    }
+
+    fn expand(&self, text: &str) -> Vec<char> {
+        let chars = text.chars().flat_map(char::to_lowercase);
+
+        if self.diacritics {
+            chars.nfd().filter(|c| !is_combining_mark(*c)).collect()
+        } else {
+            chars.collect()
+        }
+    }
+
+    pub fn apply(&self, source: &str, mode: Mode) -> String {
+        self.apply_mapped(source, mode).text
+    }
+
+    // every output character keeps the range of the source text it was normalized from
+    pub fn apply_mapped(&self, source: &str, mode: Mode) -> Normalized {
+        let segments = if self.nfkc {
+            nfkc_segments(source)
+        } else {
+            source
+                .char_indices()
+                .map(|(start, c)| (start..start + c.len_utf8(), c.to_string()))
+                .collect()
+        };
+        let expanded = segments
+            .into_iter()
+            .flat_map(|(origin, text)| {
+                self.expand(&text)
+                    .into_iter()
+                    .map(move |c| (c, origin.clone()))
+            })
+            .collect::<Vec<_>>();
+        let char_at =
+            |index: Option<usize>| index.and_then(|index| expanded.get(index)).map(|(c, _)| *c);
+        let mut normalized = Normalized::default();
+
+        for (index, (c, origin)) in expanded.iter().enumerate() {
+            let neighbours = [char_at(index.checked_sub(1)), char_at(Some(index + 1))];
+            let mut c = self.fold_char(*c, neighbours);
+            let last = normalized.text.chars().next_back();
+
+            if c.is_whitespace() {
+                match mode {
+                    Mode::Substring => continue,
+                    Mode::Word if last.map_or(true, |last| last == ' ') => continue,
+                    Mode::Word => c = ' ',
+                    Mode::Regex => {}
+                }
+            }
+            if self.collapse && last == Some(c) {
+                continue;
+            }
+
+            normalized.push(c, origin.clone());
+        }
+
+        if mode == Mode::Word && normalized.text.ends_with(' ') {
+            normalized.text.pop();
+            normalized.origins.pop();
+        }
+
+        normalized
+    }
+}
+
+// composition can span several characters, so the whole text is normalized at once and then
+// split back into the shortest source ranges whose own normalization matches it in order
+fn nfkc_segments(source: &str) -> Vec<(Range<usize>, String)> {
+    let full = source.nfkc().collect::<String>();
+    let mut segments = vec![];
+    let mut start = 0;
+    let mut offset = 0;
+
+    for (index, c) in source.char_indices() {
+        let end = index + c.len_utf8();
+        let piece = source[start..end].nfkc().collect::<String>();
+
+        if full[offset..].starts_with(&piece) {
+            offset += piece.len();
+            segments.push((start..end, piece));
+            start = end;
+        }
+    }
+
+    if start < source.len() || offset < full.len() {
+        return vec![(0..source.len(), full)];
+    }
+
+    segments
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Normalized {
+    pub text: String,
+    origins: Vec<(usize, Range<usize>)>,
+}
+
+impl Normalized {
+    fn push(&mut self, c: char, origin: Range<usize>) {
+        self.origins.push((self.text.len(), origin));
+        self.text.push(c);
+    }
+
+    pub fn origin_of(&self, range: &Range<usize>) -> Range<usize> {
+        let index = |offset: usize| match self.origins.binary_search_by_key(&offset, |o| o.0) {
+            Ok(index) | Err(index) => index,
+        };
+        let first = index(range.start);
+        let last = index(range.end).saturating_sub(1).max(first);
+
+        match (self.origins.get(first), self.origins.get(last)) {
+            (Some((_, start)), Some((_, end))) => start.start..end.end,
+            _ => 0..0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn homoglyphs() -> Normalize {
+        Normalize {
+            homoglyphs: true,
+            ..Normalize::default()
+        }
+    }
+
+    #[test]
+    fn nfkc_composes_across_characters() {
+        let nfkc = Normalize {
+            nfkc: true,
+            ..Normalize::default()
+        };
+        let normalized = nfkc.apply_mapped("cafe\u{301}!", Mode::Substring);
+
+        assert_eq!(normalized.text, "caf\u{e9}!");
+        assert_eq!(normalized.origin_of(&(3..5)), 3..6);
+    }
+
+    #[test]
+    fn leetspeak_within_words() {
+        assert_eq!(homoglyphs().apply("m4n", Mode::Word), "man");
+        assert_eq!(homoglyphs().apply("sh!t", Mode::Word), "shit");
+        assert_eq!(homoglyphs().apply("m0nk3", Mode::Word), "monke");
+        assert_eq!(homoglyphs().apply("4pe", Mode::Word), "ape");
+    }
+
+    #[test]
+    fn leetspeak_outside_words() {
+        assert_eq!(homoglyphs().apply("ape!", Mode::Word), "ape!");
+        assert_eq!(homoglyphs().apply("1 man", Mode::Word), "1 man");
+        assert_eq!(homoglyphs().apply("!ape", Mode::Word), "!ape");
+        assert_eq!(homoglyphs().apply("1337", Mode::Word), "1337");
+    }
+}
@@ -1,12 +1,18 @@
 use std::{
     fs::{create_dir_all, remove_file, File},
-    io::{Read, Write},
+    io::{ErrorKind, Read, Write},
     ops::{Deref, DerefMut},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use crate::prelude::*;
 
+pub fn is_not_found(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|error| error.kind() == ErrorKind::NotFound)
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Kind {
     Rmp,
@@ -19,11 +25,51 @@ impl Kind {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Layer<T> {
+    Extend(T),
+    Override(T),
+}
+
+impl<T> Layer<T>
+where
+    T: Clone + Merge,
+{
+    pub fn resolve(&self, global: &T) -> T {
+        match self {
+            Self::Extend(value) => {
+                let mut global = global.clone();
+                global.merge(value);
+                global
+            }
+            Self::Override(value) => value.clone(),
+        }
+    }
+}
+
+pub trait Merge {
+    fn merge(&mut self, other: &Self);
+}
+
+impl<T> Merge for Vec<T>
+where
+    T: Clone + PartialEq,
+{
+    fn merge(&mut self, other: &Self) {
+        for value in other {
+            if !self.contains(value) {
+                self.push(value.clone());
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Stored<'s, T>
 where
     T: Serialize + for<'de> Deserialize<'de>,
 {
+    guild: Option<GuildId>,
     dir: &'s str,
     key: &'s str,
     kind: Kind,
@@ -35,19 +81,44 @@ where
     T: Serialize + for<'de> Deserialize<'de>,
 {
     pub const DIR: &str = "data";
+    pub const GUILDS: &str = "guilds";
 
     pub fn ext(&self) -> String {
         format!("{:?}", self.kind).to_lowercase()
     }
     pub fn dir(&self) -> PathBuf {
-        PathBuf::from(Self::DIR).join(self.dir)
+        Self::dir_of(self.guild, self.dir)
     }
     pub fn path(&self) -> PathBuf {
         self.dir().join(self.key).with_extension(self.ext())
     }
+    pub fn dir_of(guild: Option<GuildId>, dir: &str) -> PathBuf {
+        let mut root = PathBuf::from(Self::DIR);
+
+        if let Some(id) = guild {
+            root = root.join(Self::GUILDS).join(id.to_string());
+        }
+
+        root.join(dir)
+    }
+    pub fn path_of(guild: Option<GuildId>, dir: &str, key: &str, kind: Kind) -> PathBuf {
+        Self::dir_of(guild, dir)
+            .join(key)
+            .with_extension(kind.ext())
+    }
 
     pub const fn new(dir: &'s str, key: &'s str, kind: Kind, value: T) -> Self {
+        Self::new_in(None, dir, key, kind, value)
+    }
+    pub const fn new_in(
+        guild: Option<GuildId>,
+        dir: &'s str,
+        key: &'s str,
+        kind: Kind,
+        value: T,
+    ) -> Self {
         Self {
+            guild,
             dir,
             key,
             kind,
@@ -55,27 +126,43 @@ where
         }
     }
     pub fn read(dir: &'s str, key: &'s str, kind: Kind) -> Result<Self> {
-        let path = PathBuf::from(Self::DIR)
-            .join(dir)
-            .join(key)
-            .with_extension(kind.ext());
+        Self::read_in(None, dir, key, kind)
+    }
+    pub fn read_in(guild: Option<GuildId>, dir: &'s str, key: &'s str, kind: Kind) -> Result<Self> {
+        let value = Self::read_path(&Self::path_of(guild, dir, key, kind), kind)?;
 
+        Ok(Self::new_in(guild, dir, key, kind, value))
+    }
+    pub fn read_or_else(
+        guild: Option<GuildId>,
+        dir: &'s str,
+        key: &'s str,
+        kind: Kind,
+        default: impl FnOnce() -> T,
+    ) -> Result<Self> {
+        match Self::read_in(guild, dir, key, kind) {
+            Err(error) if is_not_found(&error) => {
+                Ok(Self::new_in(guild, dir, key, kind, default()))
+            }
+            result => result,
+        }
+    }
+    pub fn read_path(path: &Path, kind: Kind) -> Result<T> {
         let mut file = File::open(path)?;
-        let value = match kind {
+
+        Ok(match kind {
             Kind::Rmp => rmp_serde::from_read(file)?,
             Kind::Ron => {
                 let mut string = String::new();
                 file.read_to_string(&mut string)?;
                 ron::from_str(&string)?
             }
-        };
-
-        Ok(Self::new(dir, key, kind, value))
+        })
     }
 
     pub fn storage_resync(self) -> Result<(T, Self)> {
         let old = self.value;
-        let new = Self::read(self.dir, self.key, self.kind)?;
+        let new = Self::read_in(self.guild, self.dir, self.key, self.kind)?;
 
         Ok((old, new))
     }
@@ -96,8 +183,9 @@ where
         Ok(self.value)
     }
     pub fn storage_rename(self, dir: &'s str, key: &'s str, kind: Kind) -> Result<Self> {
+        let guild = self.guild;
         let value = self.storage_delete()?;
-        let moved = Self::new(dir, key, kind, value);
+        let moved = Self::new_in(guild, dir, key, kind, value);
 
         moved.storage_write()?;
         Ok(moved)
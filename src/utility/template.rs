@@ -0,0 +1,253 @@
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use serde::{
+    de::{self, Visitor},
+    Deserializer, Serializer,
+};
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placeholder {
+    Author,
+    AuthorMention,
+    Channel,
+    Guild,
+    Trigger,
+    Time,
+}
+
+impl Placeholder {
+    pub const ALL: [Self; 6] = [
+        Self::Author,
+        Self::AuthorMention,
+        Self::Channel,
+        Self::Guild,
+        Self::Trigger,
+        Self::Time,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Author => "author",
+            Self::AuthorMention => "author.mention",
+            Self::Channel => "channel",
+            Self::Guild => "guild",
+            Self::Trigger => "trigger",
+            Self::Time => "time",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Placeholder(Placeholder),
+    Choice(Vec<String>),
+    Range(i64, i64),
+}
+
+impl Part {
+    fn parse(inner: &str) -> Result<Self> {
+        if inner.contains('|') {
+            return Ok(Self::Choice(inner.split('|').map(str::to_string).collect()));
+        }
+
+        if let Some((start, end)) = inner.split_once("..") {
+            let (Ok(start), Ok(end)) = (start.trim().parse(), end.trim().parse()) else {
+                return Err(anyhow!("\"{{{inner}}}\" is not a valid range!"));
+            };
+
+            return if start <= end {
+                Ok(Self::Range(start, end))
+            } else {
+                Err(anyhow!("\"{{{inner}}}\" must start at its lowest number!"))
+            };
+        }
+
+        Placeholder::ALL
+            .into_iter()
+            .find(|p| p.name() == inner.trim())
+            .map(Self::Placeholder)
+            .ok_or_else(|| anyhow!("Unknown placeholder \"{{{inner}}}\"!"))
+    }
+}
+
+// names are user-controlled, so any mentions within them are escaped when rendered
+#[derive(Clone, Debug)]
+pub struct TemplateContext {
+    pub author: String,
+    pub author_id: UserId,
+    pub channel_id: ChannelId,
+    pub guild: Option<String>,
+    pub trigger: String,
+}
+
+pub fn escape_mentions(text: &str) -> String {
+    text.replace('@', "@\u{200b}")
+}
+
+// braces are written as "{{" and "}}" when they should appear literally
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn new(source: impl Into<String>) -> Result<Self> {
+        let source = source.into();
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut chars = source.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest
+                        .find('}')
+                        .ok_or_else(|| anyhow!("Unclosed \"{{\" in response!"))?;
+
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+
+                    parts.push(Part::parse(&rest[..end])?);
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => return Err(anyhow!("Unmatched \"}}\" in response!")),
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self { source, parts })
+    }
+
+    pub fn uses(&self, placeholder: Placeholder) -> bool {
+        self.parts.contains(&Part::Placeholder(placeholder))
+    }
+
+    pub fn render(&self, context: &TemplateContext) -> String {
+        let mut rng = thread_rng();
+
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Placeholder(placeholder) => match placeholder {
+                    Placeholder::Author => escape_mentions(&context.author),
+                    Placeholder::AuthorMention => format!("<@{}>", context.author_id),
+                    Placeholder::Channel => format!("<#{}>", context.channel_id),
+                    Placeholder::Guild => context
+                        .guild
+                        .as_deref()
+                        .map(escape_mentions)
+                        .unwrap_or_default(),
+                    Placeholder::Trigger => escape_mentions(&context.trigger),
+                    Placeholder::Time => format!("<t:{}:t>", Utc::now().timestamp()),
+                },
+                Part::Choice(choices) => choices.choose(&mut rng).cloned().unwrap_or_default(),
+                Part::Range(start, end) => rng.gen_range(*start..=*end).to_string(),
+            })
+            .collect()
+    }
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Serialize for Template {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Template {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TemplateVisitor;
+
+        impl<'de> Visitor<'de> for TemplateVisitor {
+            type Value = Template;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a response template string")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Template::new(v).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(TemplateVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TemplateContext {
+        TemplateContext {
+            author: "@everyone".to_string(),
+            author_id: UserId::new(1),
+            channel_id: ChannelId::new(2),
+            guild: None,
+            trigger: "Ape".to_string(),
+        }
+    }
+
+    #[test]
+    fn escaped_braces() -> Result<()> {
+        let template = Template::new("{{trigger}} }}{{")?;
+
+        assert_eq!(template.render(&context()), "{trigger} }{");
+        Ok(())
+    }
+
+    #[test]
+    fn placeholders() -> Result<()> {
+        let template = Template::new("{author} said {trigger} in {channel}{guild}")?;
+
+        assert!(template.uses(Placeholder::Trigger));
+        assert!(!template.uses(Placeholder::Time));
+        assert_eq!(
+            template.render(&context()),
+            "@\u{200b}everyone said Ape in <#2>"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn choices_and_ranges() -> Result<()> {
+        let choice = Template::new("{ook|eek}")?.render(&context());
+        let range = Template::new("{3..3}")?.render(&context());
+
+        assert!(choice == "ook" || choice == "eek");
+        assert_eq!(range, "3");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_templates() {
+        assert!(Template::new("ook {author").is_err());
+        assert!(Template::new("ook author}").is_err());
+        assert!(Template::new("{5..1}").is_err());
+        assert!(Template::new("{1..x}").is_err());
+        assert!(Template::new("{banana}").is_err());
+    }
+}